@@ -1,6 +1,7 @@
 package main
 
 import (
+	"crypto/rand"
 	"encoding/json"
 	"fmt"
 	"log"
@@ -8,7 +9,6 @@ import (
 	"os"
 	"os/exec"
 	"path/filepath"
-	"regexp"
 	"strings"
 	"time"
 )
@@ -16,6 +16,21 @@ import (
 type PasswordHistory struct {
 	Password  string `json:"password"`
 	ChangedAt uint64 `json:"changed_at"`
+	Field     string `json:"field,omitempty"`
+	Restore   bool   `json:"restore,omitempty"`
+}
+
+type CustomField struct {
+	Name  string `json:"name"`
+	Value string `json:"value"`
+	Kind  string `json:"kind,omitempty"`
+}
+
+type Attachment struct {
+	Name  string `json:"name"`
+	Size  uint64 `json:"size"`
+	Blob  string `json:"blob"`
+	Added uint64 `json:"added"`
 }
 
 type Entry struct {
@@ -29,114 +44,110 @@ type Entry struct {
 	Tags         []string          `json:"tags,omitempty"`
 	History      []PasswordHistory `json:"history,omitempty"`
 	LastModified uint64            `json:"last_modified,omitempty"`
+	Fields       []CustomField     `json:"fields,omitempty"`
+	Kind         string            `json:"kind,omitempty"`
+	Data         map[string]string `json:"data,omitempty"`
+	Otp          string            `json:"otp,omitempty"`
+	Attachments  []Attachment      `json:"attachments,omitempty"`
+	Folder       string            `json:"folder,omitempty"`
+	Favorite     bool              `json:"favorite,omitempty"`
+	Uses         uint64            `json:"uses,omitempty"`
+	RotateDays   uint32            `json:"rotate_days,omitempty"`
+	Deleted      uint64            `json:"deleted,omitempty"`
+	Breached     *uint64           `json:"breached,omitempty"`
+	Policy       json.RawMessage   `json:"policy,omitempty"`
+	PwdChangedAt uint64            `json:"pwd_changed_at,omitempty"`
 }
 
 type Vault struct {
-	E []Entry `json:"e"`
-	S string  `json:"s"`
+	E            []Entry           `json:"e"`
+	S            string            `json:"s"`
+	Created      uint64            `json:"created,omitempty"`
+	BlobKey      string            `json:"blob_key,omitempty"`
+	TagRotation  map[string]uint32 `json:"tag_rotation,omitempty"`
+	HistoryDepth *int              `json:"history_depth,omitempty"`
+	Trash        []Entry           `json:"trash,omitempty"`
+	TrashDays    *uint32           `json:"trash_days,omitempty"`
+	Tombstones   map[string]uint64 `json:"tombstones,omitempty"`
 }
 
-var v *Vault
-var ms_pwd string
-
-func calc_pwdS(password string) map[string]interface{} {
-	score := 0
-	feedback := []string{}
-
-	length := len(password)
-
-	if length >= 8 {
-		score += 1
-	}
-	if length >= 12 {
-		score += 1
-	}
-	if length >= 16 {
-		score += 1
-	}
-	if length < 8 {
-		feedback = append(feedback, "Use at least 8 characters")
-	}
-
-	hasLower := regexp.MustCompile(`[a-z]`).MatchString(password)
-	hasUpper := regexp.MustCompile(`[A-Z]`).MatchString(password)
-	hasDigit := regexp.MustCompile(`[0-9]`).MatchString(password)
-	hasSpecial := regexp.MustCompile(`[!@#$%^&*()_+\-=\[\]{};':"\\|,.<>\/?]`).MatchString(password)
-
-	varietyCount := 0
-	if hasLower {
-		varietyCount++
-	} else {
-		feedback = append(feedback, "Add lowercase letters")
-	}
-
-	if hasUpper {
-		varietyCount++
-	} else {
-		feedback = append(feedback, "Add uppercase letters")
+// newUUID returns a random RFC 4122 version 4 UUID, the id format the Rust
+// side generates and migrates legacy ids to.
+func newUUID() (string, error) {
+	b := make([]byte, 16)
+	if _, err := rand.Read(b); err != nil {
+		return "", err
 	}
+	b[6] = (b[6] & 0x0f) | 0x40
+	b[8] = (b[8] & 0x3f) | 0x80
+	return fmt.Sprintf("%x-%x-%x-%x-%x", b[0:4], b[4:6], b[6:8], b[8:10], b[10:16]), nil
+}
 
-	if hasDigit {
-		varietyCount++
-	} else {
-		feedback = append(feedback, "Add numbers")
+// pushHistory records an old value of field ("" means password) and keeps
+// at most the vault's history depth per field, like Entry::push_history.
+func pushHistory(entry *Entry, field, old string, now uint64) {
+	if field == "" {
+		// Kept apart from the history, which may be trimmed or off.
+		entry.PwdChangedAt = now
+		// The audit result was about the old password.
+		entry.Breached = nil
 	}
-
-	if hasSpecial {
-		varietyCount++
-	} else {
-		feedback = append(feedback, "Add special characters")
+	depth := 5
+	if v.HistoryDepth != nil {
+		depth = *v.HistoryDepth
 	}
-
-	score += varietyCount
-
-	regex := []string{"123", "abc", "password", "qwerty", "admin"}
-	lower_pwd := strings.ToLower(password)
-	for _, pattern := range regex {
-		if strings.Contains(lower_pwd, pattern) {
-			score -= 2
-			feedback = append(feedback, "Avoid common patterns")
-			break
+	entry.History = append(entry.History, PasswordHistory{
+		Password:  old,
+		ChangedAt: now,
+		Field:     field,
+	})
+	kept := 0
+	trimmed := []PasswordHistory{}
+	for i := len(entry.History) - 1; i >= 0; i-- {
+		h := entry.History[i]
+		if h.Field == field {
+			if kept >= depth {
+				continue
+			}
+			kept++
 		}
+		trimmed = append([]PasswordHistory{h}, trimmed...)
 	}
+	entry.History = trimmed
+}
 
-	if length >= 20 {
-		score += 1
-	}
-
-	var strength string
-	var color string
-	var percentage int
+var v *Vault
+var ms_pwd string
 
-	if score < 0 {
-		score = 0
+// strengthOf rates a password with the Rust estimator (`passlock strength`),
+// so the web UI and the TUI agree. The password goes over stdin, not argv.
+func strengthOf(password string) (map[string]interface{}, error) {
+	cmd := exec.Command("cargo", "run", "--release", "--quiet", "--", "strength")
+	cmd.Stdin = strings.NewReader(password + "\n")
+	out, err := cmd.Output()
+	if err != nil {
+		return nil, err
 	}
-
-	if score <= 2 {
-		strength = "Weak"
-		color = "red"
-		percentage = 25
-	} else if score <= 4 {
-		strength = "Fair"
-		color = "orange"
-		percentage = 50
-	} else if score <= 6 {
-		strength = "Good"
-		color = "yellow"
-		percentage = 75
-	} else {
-		strength = "Strong"
-		color = "green"
-		percentage = 100
+	result := map[string]interface{}{}
+	if err := json.Unmarshal(out, &result); err != nil {
+		return nil, err
+	}
+	colors := map[string]string{"Weak": "red", "Fair": "orange", "Good": "yellow", "Strong": "green"}
+	if s, ok := result["strength"].(string); ok {
+		result["color"] = colors[s]
 	}
+	return result, nil
+}
 
-	return map[string]interface{}{
-		"score":      score,
-		"strength":   strength,
-		"color":      color,
-		"percentage": percentage,
-		"feedback":   feedback,
+// generate runs the Rust generator (`passlock generate`), so every secret
+// comes from the vault engine's libsodium RNG.
+func generate(args ...string) (string, error) {
+	cmd := exec.Command("cargo", append([]string{"run", "--release", "--quiet", "--", "generate"}, args...)...)
+	out, err := cmd.Output()
+	if err != nil {
+		return "", err
 	}
+	return strings.TrimSpace(string(out)), nil
 }
 
 func handle(w http.ResponseWriter, r *http.Request) {
@@ -173,7 +184,11 @@ func handle(w http.ResponseWriter, r *http.Request) {
 
 	case "strength":
 		password, _ := req["password"].(string)
-		result := calc_pwdS(password)
+		result, err := strengthOf(password)
+		if err != nil {
+			json.NewEncoder(w).Encode(map[string]interface{}{"ok": false, "msg": "strength check failed"})
+			return
+		}
 		json.NewEncoder(w).Encode(map[string]interface{}{"ok": true, "data": result})
 
 	case "create":
@@ -348,9 +363,15 @@ func handle(w http.ResponseWriter, r *http.Request) {
 			return
 		}
 
+		id, err := newUUID()
+		if err != nil {
+			json.NewEncoder(w).Encode(map[string]interface{}{"ok": false, "msg": "rng failed"})
+			return
+		}
+
 		now := uint64(time.Now().Unix())
 		e := Entry{
-			ID:           fmt.Sprintf("%d", time.Now().UnixNano()),
+			ID:           id,
 			N:            name,
 			U:            user,
 			P:            pass,
@@ -398,13 +419,13 @@ func handle(w http.ResponseWriter, r *http.Request) {
 				now := uint64(time.Now().Unix())
 
 				if oldPass != pass && pass != "" {
-					if len(entry.History) >= 5 {
-						entry.History = entry.History[1:]
-					}
-					entry.History = append(entry.History, PasswordHistory{
-						Password:  oldPass,
-						ChangedAt: now,
-					})
+					pushHistory(&entry, "", oldPass, now)
+				}
+				if user != "" && entry.U != user {
+					pushHistory(&entry, "username", entry.U, now)
+				}
+				if entry.Url != url {
+					pushHistory(&entry, "url", entry.Url, now)
 				}
 
 				if name != "" {
@@ -448,6 +469,9 @@ func handle(w http.ResponseWriter, r *http.Request) {
 		for _, e := range v.E {
 			if e.ID != id {
 				newE = append(newE, e)
+			} else {
+				e.Deleted = uint64(time.Now().Unix())
+				v.Trash = append(v.Trash, e)
 			}
 		}
 		v.E = newE
@@ -469,13 +493,12 @@ func handle(w http.ResponseWriter, r *http.Request) {
 			l = 64
 		}
 
-		chars := "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%^&*"
-		pwd := make([]byte, l)
-		for i := range pwd {
-			pwd[i] = chars[time.Now().UnixNano()%int64(len(chars))]
-			time.Sleep(time.Nanosecond)
+		pwd, err := generate("--length", fmt.Sprint(l))
+		if err != nil {
+			json.NewEncoder(w).Encode(map[string]interface{}{"ok": false, "msg": "failed to generate password"})
+			return
 		}
-		json.NewEncoder(w).Encode(map[string]interface{}{"ok": true, "data": string(pwd)})
+		json.NewEncoder(w).Encode(map[string]interface{}{"ok": true, "data": pwd})
 
 	case "save":
 		if v == nil {
@@ -0,0 +1,176 @@
+use crate::crypto;
+use crate::models::{Attachment, Vault};
+use crate::storage;
+use crate::vault_ffi;
+use std::collections::HashSet;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Largest attachment accepted, in bytes.
+pub const MAX_SIZE: usize = 10 * 1024 * 1024;
+
+fn blob_path(dir: &Path, blob: &str) -> PathBuf {
+    dir.join(format!("{blob}.blob"))
+}
+
+/// Content address of `data`. Keyed with the vault's secret blob key so the
+/// file names on disk don't reveal which well-known files are attached.
+fn blob_id(v: &Vault, data: &[u8]) -> Result<String, String> {
+    let mut keyed = hex::decode(&v.blob_key).map_err(|_| "Invalid blob key")?;
+    keyed.extend_from_slice(data);
+    let digest = vault_ffi::hash(&keyed, 32);
+    crypto::secure_wipe(&mut keyed);
+    Ok(hex::encode(digest?))
+}
+
+/// Encrypts `data` into the blob store and returns the reference to keep on
+/// an entry. Identical content is stored once.
+pub fn put(v: &mut Vault, pwd: &str, name: &str, data: &[u8]) -> Result<Attachment, String> {
+    put_in(&storage::blobs_p(), v, pwd, name, data)
+}
+
+pub fn get(v: &Vault, pwd: &str, a: &Attachment) -> Result<Vec<u8>, String> {
+    get_in(&storage::blobs_p(), v, pwd, a)
+}
+
+/// Deletes blobs no entry, live or trashed, refers to any more. Returns how many were removed.
+pub fn gc(v: &Vault) -> Result<usize, String> {
+    gc_in(&storage::blobs_p(), v)
+}
+
+fn put_in(
+    dir: &Path,
+    v: &mut Vault,
+    pwd: &str,
+    name: &str,
+    data: &[u8],
+) -> Result<Attachment, String> {
+    if data.len() > MAX_SIZE {
+        return Err(format!(
+            "Attachment is {} KiB, the limit is {} KiB",
+            data.len() / 1024,
+            MAX_SIZE / 1024
+        ));
+    }
+    if v.blob_key.is_empty() {
+        v.blob_key = crypto::gen_salt()?;
+    }
+    let blob = blob_id(v, data)?;
+    let path = blob_path(dir, &blob);
+    if !path.exists() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700)).map_err(|e| e.to_string())?;
+        let enc_d = crypto::enc(data, pwd, &v.s)?;
+        fs::write(&path, enc_d).map_err(|e| e.to_string())?;
+    }
+    Ok(Attachment {
+        name: name.to_string(),
+        size: data.len() as u64,
+        blob,
+        added: crate::get_timestamp(),
+    })
+}
+
+fn get_in(dir: &Path, v: &Vault, pwd: &str, a: &Attachment) -> Result<Vec<u8>, String> {
+    let enc_d = fs::read(blob_path(dir, &a.blob))
+        .map_err(|_| format!("blob for '{}' is missing", a.name))?;
+    let data = crypto::dec(&enc_d, pwd, &v.s)?;
+    if blob_id(v, &data)? != a.blob {
+        return Err(format!("blob for '{}' does not match its hash", a.name));
+    }
+    Ok(data)
+}
+
+fn gc_in(dir: &Path, v: &Vault) -> Result<usize, String> {
+    let Ok(dir) = fs::read_dir(dir) else {
+        return Ok(0);
+    };
+    let live: HashSet<&str> =
+        v.e.iter()
+            .chain(&v.trash)
+            .flat_map(|e| e.attachments.iter().map(|a| a.blob.as_str()))
+            .collect();
+    let mut removed = 0;
+    for item in dir.flatten() {
+        let path = item.path();
+        let Some(blob) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".blob"))
+        else {
+            continue;
+        };
+        if !live.contains(blob) {
+            fs::remove_file(&path).map_err(|e| e.to_string())?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Human-readable size, e.g. `12 KB`.
+pub fn fmt_size(size: u64) -> String {
+    match size {
+        s if s < 1024 => format!("{s} B"),
+        s if s < 1024 * 1024 => format!("{} KB", s / 1024),
+        s => format!("{:.1} MB", s as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Entry;
+
+    #[test]
+    fn test_blob_store() {
+        let dir = std::env::temp_dir().join(format!("passlock-blobs-{}", std::process::id()));
+        let dir = dir.as_path();
+        crypto::init_crypto().unwrap();
+        let pwd = "pw";
+        let mut v = Vault::new(crypto::gen_salt().unwrap());
+        let blobs = || fs::read_dir(dir).unwrap().count();
+
+        let a = put_in(dir, &mut v, pwd, "a.txt", b"hello").unwrap();
+        assert_eq!(a.size, 5);
+        assert_eq!(get_in(dir, &v, pwd, &a).unwrap(), b"hello");
+        let b = put_in(dir, &mut v, pwd, "b.txt", b"hello").unwrap();
+        assert_eq!(a.blob, b.blob);
+        assert_eq!(blobs(), 1);
+
+        assert!(put_in(dir, &mut v, pwd, "big", &vec![0; MAX_SIZE + 1]).is_err());
+        assert_eq!(blobs(), 1);
+
+        // A blob swapped for other validly encrypted data fails the check.
+        let c = put_in(dir, &mut v, pwd, "c.txt", b"other").unwrap();
+        let swapped = fs::read(blob_path(dir, &c.blob)).unwrap();
+        fs::write(blob_path(dir, &a.blob), &swapped).unwrap();
+        assert!(get_in(dir, &v, pwd, &a)
+            .unwrap_err()
+            .contains("does not match"));
+        fs::write(
+            blob_path(dir, &a.blob),
+            crypto::enc(b"hello", pwd, &v.s).unwrap(),
+        )
+        .unwrap();
+
+        let mut live: Entry =
+            serde_json::from_str(r#"{"id":"1","n":"a","u":"","p":"","t":0}"#).unwrap();
+        live.attachments.push(a.clone());
+        let mut trashed = live.clone();
+        trashed.id = "2".to_string();
+        trashed.attachments = vec![c.clone()];
+        v.e.push(live);
+        v.trash.push(trashed);
+        fs::write(blob_path(dir, "stale"), b"x").unwrap();
+        assert_eq!(gc_in(dir, &v).unwrap(), 1);
+        assert_eq!(blobs(), 2);
+        assert!(get_in(dir, &v, pwd, &c).is_ok());
+
+        v.trash.clear();
+        assert_eq!(gc_in(dir, &v).unwrap(), 1);
+        assert!(get_in(dir, &v, pwd, &c).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,198 @@
+use crate::crypto;
+use crate::models::{Entry, EntryKind, Vault};
+use std::collections::BTreeMap;
+
+/// Passwords older than this many days are reported unless configured
+/// otherwise.
+pub const DEFAULT_MAX_AGE: u32 = 365;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    Breached,
+    Reused,
+    Weak,
+    Old,
+    Insecure,
+    Duplicate,
+    MissingUser,
+    MissingUrl,
+}
+
+impl Kind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Kind::Breached => "Breached",
+            Kind::Reused => "Reused",
+            Kind::Weak => "Weak",
+            Kind::Old => "Old",
+            Kind::Insecure => "Insecure URL",
+            Kind::Duplicate => "Duplicate",
+            Kind::MissingUser => "No username",
+            Kind::MissingUrl => "No URL",
+        }
+    }
+}
+
+/// One problem with one entry. Problems shared by a group (reuse,
+/// duplicates) yield a finding per member so each can be jumped to.
+#[derive(Clone, Debug)]
+pub struct Finding {
+    pub kind: Kind,
+    pub id: String,
+    pub path: String,
+    pub detail: String,
+}
+
+fn finding(kind: Kind, e: &Entry, detail: String) -> Finding {
+    Finding {
+        kind,
+        id: e.id.clone(),
+        path: e.path(),
+        detail,
+    }
+}
+
+/// Members of each group of two or more entries sharing `key`.
+fn groups(v: &Vault, key: impl Fn(&Entry) -> Option<String>) -> Vec<Vec<&Entry>> {
+    let mut map: BTreeMap<String, Vec<&Entry>> = BTreeMap::new();
+    for e in &v.e {
+        if let Some(k) = key(e) {
+            map.entry(k).or_default().push(e);
+        }
+    }
+    map.into_values().filter(|g| g.len() > 1).collect()
+}
+
+fn push_groups(out: &mut Vec<Finding>, kind: Kind, groups: Vec<Vec<&Entry>>) {
+    for g in groups {
+        for e in &g {
+            let others: Vec<String> = g
+                .iter()
+                .filter(|o| o.id != e.id)
+                .map(|o| o.path())
+                .collect();
+            out.push(finding(kind, e, format!("same as {}", others.join(", "))));
+        }
+    }
+}
+
+/// Runs every check over the live entries, most serious findings first.
+pub fn run(v: &Vault, now: u64, max_age_days: u32) -> Vec<Finding> {
+    let mut out = Vec::new();
+    for e in &v.e {
+        if let Some(n) = e.breached {
+            let detail = match n {
+                0 => "found in a breach filter".to_string(),
+                n => format!("seen {n} times in breaches"),
+            };
+            out.push(finding(Kind::Breached, e, detail));
+        }
+    }
+    push_groups(
+        &mut out,
+        Kind::Reused,
+        groups(v, |e| (!e.p.is_empty()).then(|| e.p.clone())),
+    );
+    for e in v.e.iter().filter(|e| !e.p.is_empty()) {
+        let s = crypto::calc_pwd_strength(&e.p, &[&e.n, &e.u]);
+        if s.strength == "Weak" {
+            // Findings end up in cron mail and logs, so they only grade the
+            // password and never describe it.
+            out.push(finding(
+                Kind::Weak,
+                e,
+                format!("{}, cracked in {}", s.strength.to_lowercase(), s.crack_time),
+            ));
+        }
+        let days = now.saturating_sub(e.pwd_changed()) / 86400;
+        if days > u64::from(max_age_days) {
+            out.push(finding(Kind::Old, e, format!("unchanged for {days} days")));
+        }
+    }
+    for e in &v.e {
+        if let Some(url) = e
+            .url
+            .as_deref()
+            .filter(|u| u.trim().to_lowercase().starts_with("http://"))
+        {
+            out.push(finding(Kind::Insecure, e, url.to_string()));
+        }
+    }
+    push_groups(
+        &mut out,
+        Kind::Duplicate,
+        groups(v, |e| {
+            Some(format!(
+                "{:?}\n{}\n{}\n{}",
+                e.kind,
+                e.n.trim().to_lowercase(),
+                e.u.trim().to_lowercase(),
+                e.url.as_deref().unwrap_or_default().trim().to_lowercase()
+            ))
+        }),
+    );
+    for e in v.e.iter().filter(|e| e.kind == EntryKind::Login) {
+        if e.u.trim().is_empty() {
+            out.push(finding(Kind::MissingUser, e, String::new()));
+        }
+        if e.url.as_deref().unwrap_or_default().trim().is_empty() {
+            out.push(finding(Kind::MissingUrl, e, String::new()));
+        }
+    }
+    out.sort_by_key(|f| f.kind);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, n: &str, u: &str, p: &str, url: Option<&str>) -> Entry {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "n": n, "u": u, "p": p, "url": url, "t": 0,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_audit() {
+        let day = 86400;
+        let strong = "vN4#qT9!xLw2^zR7";
+        let mut v = Vault::new(String::new());
+        v.e = vec![
+            entry("a", "mail", "me", strong, Some("https://mail.example")),
+            entry("b", "bank", "me", strong, Some("http://bank.example")),
+            entry("c", "forum", "", "password1", None),
+            entry(
+                "d",
+                "Mail",
+                "me",
+                "x7$Kp2!rQz9#mW4v",
+                Some("https://mail.example"),
+            ),
+        ];
+        v.e[3].t = 400 * day;
+        let found = run(&v, 400 * day, 365);
+        let ids = |k: Kind| -> Vec<&str> {
+            found
+                .iter()
+                .filter(|f| f.kind == k)
+                .map(|f| f.id.as_str())
+                .collect()
+        };
+        assert_eq!(ids(Kind::Reused), ["a", "b"]);
+        assert_eq!(ids(Kind::Weak), ["c"]);
+        let weak = found.iter().find(|f| f.kind == Kind::Weak).unwrap();
+        assert!(
+            weak.detail.starts_with("weak, cracked in "),
+            "{}",
+            weak.detail
+        );
+        assert_eq!(ids(Kind::Old), ["a", "b", "c"]);
+        assert_eq!(ids(Kind::Insecure), ["b"]);
+        assert_eq!(ids(Kind::Duplicate), ["a", "d"]);
+        assert_eq!(ids(Kind::MissingUser), ["c"]);
+        assert_eq!(ids(Kind::MissingUrl), ["c"]);
+        assert_eq!(found[0].kind, Kind::Reused);
+    }
+}
@@ -0,0 +1,205 @@
+use sha1::{Digest, Sha1};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+
+/// Magic at the start of a filter file written by `build_filter`.
+const BLOOM_MAGIC: &[u8; 8] = b"PLBLOOM1";
+const BLOOM_HEADER: u64 = 28;
+
+/// An offline breach corpus: either the Pwned Passwords SHA-1 list ordered
+/// by hash (`HASH:count` per line), searched in place, or a compact Bloom
+/// filter built from it.
+pub enum Corpus {
+    Sorted { file: File, len: u64 },
+    Bloom { file: File, k: u32, m: u64 },
+}
+
+pub fn sha1_hex(password: &str) -> String {
+    hex::encode_upper(Sha1::digest(password.as_bytes()))
+}
+
+/// Bit positions for `digest` (Kirsch-Mitzenmacher double hashing; SHA-1
+/// output is already uniform, so its bytes are the two base hashes).
+fn bloom_bits(digest: &[u8], k: u32, m: u64) -> impl Iterator<Item = u64> {
+    let word = |b: &[u8]| u64::from_le_bytes(b.try_into().unwrap_or_default());
+    let h1 = word(&digest[0..8]);
+    let h2 = word(&digest[8..16]) | 1;
+    (0..u64::from(k)).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % m)
+}
+
+impl Corpus {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let len = file.metadata().map_err(|e| e.to_string())?.len();
+        let mut head = [0u8; BLOOM_HEADER as usize];
+        if len >= BLOOM_HEADER
+            && file.read_exact_at(&mut head, 0).is_ok()
+            && head[..8] == *BLOOM_MAGIC
+        {
+            let k = u32::from_le_bytes(head[8..12].try_into().unwrap_or_default());
+            let m = u64::from_le_bytes(head[12..20].try_into().unwrap_or_default());
+            if k == 0 || m == 0 || len < BLOOM_HEADER + m.div_ceil(8) {
+                return Err(format!("{}: truncated filter", path.display()));
+            }
+            return Ok(Corpus::Bloom { file, k, m });
+        }
+        Ok(Corpus::Sorted { file, len })
+    }
+
+    /// How often `password` was seen. A filter has no counts and answers
+    /// `Some(0)` on a hit, with a small false-positive rate.
+    pub fn lookup(&self, password: &str) -> Result<Option<u64>, String> {
+        match self {
+            Corpus::Sorted { file, len } => sorted_lookup(file, *len, &sha1_hex(password)),
+            Corpus::Bloom { file, k, m } => {
+                let digest = Sha1::digest(password.as_bytes());
+                for bit in bloom_bits(&digest, *k, *m) {
+                    let mut byte = [0u8];
+                    file.read_exact_at(&mut byte, BLOOM_HEADER + bit / 8)
+                        .map_err(|e| e.to_string())?;
+                    if byte[0] & (1 << (bit % 8)) == 0 {
+                        return Ok(None);
+                    }
+                }
+                Ok(Some(0))
+            }
+        }
+    }
+}
+
+/// The first line starting at or after `pos`: its start, the offset just
+/// past it, and the line itself.
+fn line_at(file: &File, len: u64, pos: u64) -> Result<Option<(u64, u64, String)>, String> {
+    let mut start = pos;
+    let mut buf = [0u8; 256];
+    if pos > 0 {
+        // Skip the rest of the line `pos` lands in.
+        let n = file.read_at(&mut buf, pos - 1).map_err(|e| e.to_string())?;
+        let nl = buf[..n].iter().position(|&b| b == b'\n');
+        match nl {
+            Some(i) => start = pos + i as u64,
+            None => return Ok(None),
+        }
+    }
+    if start >= len {
+        return Ok(None);
+    }
+    let n = file.read_at(&mut buf, start).map_err(|e| e.to_string())?;
+    let end = buf[..n].iter().position(|&b| b == b'\n').unwrap_or(n);
+    let line = String::from_utf8_lossy(&buf[..end]).trim_end().to_string();
+    Ok(Some((start, start + end as u64 + 1, line)))
+}
+
+fn parse_line(line: &str) -> (&str, u64) {
+    let (hash, count) = line.split_once(':').unwrap_or((line, "0"));
+    (hash, count.trim().parse().unwrap_or(0))
+}
+
+/// Binary search over byte offsets of a file sorted by hash.
+fn sorted_lookup(file: &File, len: u64, target: &str) -> Result<Option<u64>, String> {
+    let (mut lo, mut hi) = (0, len);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let Some((_, end, line)) = line_at(file, len, mid)? else {
+            hi = mid;
+            continue;
+        };
+        let (hash, count) = parse_line(&line);
+        match hash.to_ascii_uppercase().as_str().cmp(target) {
+            std::cmp::Ordering::Less => lo = end,
+            std::cmp::Ordering::Equal => return Ok(Some(count)),
+            std::cmp::Ordering::Greater => hi = mid,
+        }
+    }
+    match line_at(file, len, lo)? {
+        Some((_, _, line)) if parse_line(&line).0.eq_ignore_ascii_case(target) => {
+            Ok(Some(parse_line(&line).1))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Builds a Bloom filter with false-positive rate `fpr` from a SHA-1 list
+/// (one `HASH[:count]` per line, any order). Returns the number of hashes.
+pub fn build_filter(src: &Path, out: &Path, fpr: f64) -> Result<u64, String> {
+    let open = || {
+        File::open(src)
+            .map(BufReader::new)
+            .map_err(|e| format!("{}: {e}", src.display()))
+    };
+    let n = open()?.lines().map_while(Result::ok).count().max(1) as u64;
+    let ln2 = std::f64::consts::LN_2;
+    let m = ((-(n as f64) * fpr.ln()) / (ln2 * ln2)).ceil().max(64.0) as u64;
+    let k = ((m as f64 / n as f64) * ln2).round().clamp(1.0, 30.0) as u32;
+
+    let mut bits = vec![0u8; m.div_ceil(8) as usize];
+    let mut added: u64 = 0;
+    for line in open()?.lines().map_while(Result::ok) {
+        let Ok(digest) = hex::decode(parse_line(line.trim()).0) else {
+            continue;
+        };
+        if digest.len() != 20 {
+            continue;
+        }
+        for bit in bloom_bits(&digest, k, m) {
+            bits[(bit / 8) as usize] |= 1 << (bit % 8);
+        }
+        added += 1;
+    }
+
+    let file = File::create(out).map_err(|e| format!("{}: {e}", out.display()))?;
+    let mut w = BufWriter::new(file);
+    let write = |w: &mut BufWriter<File>, b: &[u8]| w.write_all(b).map_err(|e| e.to_string());
+    write(&mut w, BLOOM_MAGIC)?;
+    write(&mut w, &k.to_le_bytes())?;
+    write(&mut w, &m.to_le_bytes())?;
+    write(&mut w, &added.to_le_bytes())?;
+    write(&mut w, &bits)?;
+    w.flush().map_err(|e| e.to_string())?;
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus_file(name: &str) -> std::path::PathBuf {
+        let mut hashes: Vec<String> = (0..500)
+            .map(|i| format!("{}:{}", sha1_hex(&format!("pw{i}")), i + 1))
+            .collect();
+        hashes.sort();
+        let path = std::env::temp_dir().join(format!("passlock-{name}-{}", std::process::id()));
+        std::fs::write(&path, hashes.join("\r\n")).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_sorted_lookup() {
+        let path = corpus_file("sorted");
+        let corpus = Corpus::open(&path).unwrap();
+        for i in [0, 1, 250, 499] {
+            assert_eq!(corpus.lookup(&format!("pw{i}")).unwrap(), Some(i + 1));
+        }
+        assert_eq!(corpus.lookup("pw500").unwrap(), None);
+        assert_eq!(corpus.lookup("").unwrap(), None);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_bloom() {
+        let src = corpus_file("bloom-src");
+        let out = src.with_extension("bin");
+        assert_eq!(build_filter(&src, &out, 0.001).unwrap(), 500);
+        let corpus = Corpus::open(&out).unwrap();
+        assert!(matches!(corpus, Corpus::Bloom { .. }));
+        assert!((0..500).all(|i| corpus.lookup(&format!("pw{i}")).unwrap() == Some(0)));
+        let false_hits = (500..2500)
+            .filter(|i| corpus.lookup(&format!("pw{i}")).unwrap().is_some())
+            .count();
+        assert!(false_hits < 20, "{false_hits}");
+        std::fs::remove_file(src).unwrap();
+        std::fs::remove_file(out).unwrap();
+    }
+}
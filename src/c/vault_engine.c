@@ -3,6 +3,12 @@
 #include <string.h>
 #include <stdlib.h>
 
+// The one definition of the key derivation parameters, shared by
+// vault_derive_key and vault_kdf_params.
+#define VAULT_KDF_OPSLIMIT crypto_pwhash_OPSLIMIT_INTERACTIVE
+#define VAULT_KDF_MEMLIMIT crypto_pwhash_MEMLIMIT_INTERACTIVE
+#define VAULT_KDF_ALG crypto_pwhash_ALG_ARGON2ID13
+
 __attribute__((used))
 int vault_init(void) {
     if (sodium_init() < 0) {
@@ -22,13 +28,65 @@ void vault_secure_zero(void *ptr, size_t len) {
 
 __attribute__((used))
 int vault_gen_salt(unsigned char *salt, size_t salt_len) {
-    if (!salt || salt_len == 0) {
+    if (!salt || salt_len == 0 || sodium_init() < 0) {
         return VAULT_ERROR;
     }
     randombytes_buf(salt, salt_len);
     return VAULT_SUCCESS;
 }
 
+__attribute__((used))
+int vault_random_bytes(unsigned char *buf, size_t len) {
+    if (!buf || len == 0 || sodium_init() < 0) {
+        return VAULT_ERROR;
+    }
+    randombytes_buf(buf, len);
+    return VAULT_SUCCESS;
+}
+
+__attribute__((used))
+int vault_random_uniform(uint32_t upper_bound, uint32_t *out) {
+    if (!out || upper_bound == 0 || sodium_init() < 0) {
+        return VAULT_ERROR;
+    }
+    *out = randombytes_uniform(upper_bound);
+    return VAULT_SUCCESS;
+}
+
+__attribute__((used))
+void vault_kdf_params(
+    unsigned long long *opslimit_out,
+    size_t *memlimit_out,
+    int *alg_out
+) {
+    if (opslimit_out) {
+        *opslimit_out = VAULT_KDF_OPSLIMIT;
+    }
+    if (memlimit_out) {
+        *memlimit_out = VAULT_KDF_MEMLIMIT;
+    }
+    if (alg_out) {
+        *alg_out = VAULT_KDF_ALG;
+    }
+}
+
+__attribute__((used))
+int vault_hash(
+    const unsigned char *in,
+    size_t in_len,
+    unsigned char *out,
+    size_t out_len
+) {
+    if ((!in && in_len > 0) || !out || out_len == 0) {
+        return VAULT_ERROR;
+    }
+    // BLAKE2b, unkeyed
+    if (crypto_generichash(out, out_len, in, in_len, NULL, 0) != 0) {
+        return VAULT_ERROR_CRYPTO;
+    }
+    return VAULT_SUCCESS;
+}
+
 __attribute__((used))
 int vault_derive_key(
     const char *password,
@@ -46,9 +104,9 @@ int vault_derive_key(
             password,
             password_len,
             salt,
-            crypto_pwhash_OPSLIMIT_INTERACTIVE,
-            crypto_pwhash_MEMLIMIT_INTERACTIVE,
-            crypto_pwhash_ALG_ARGON2ID13
+            VAULT_KDF_OPSLIMIT,
+            VAULT_KDF_MEMLIMIT,
+            VAULT_KDF_ALG
         ) != 0) {
         return VAULT_ERROR_CRYPTO;
     }
@@ -52,6 +52,23 @@ int vault_derive_key(
 
 int vault_gen_salt(unsigned char *salt, size_t salt_len);
 
+int vault_random_bytes(unsigned char *buf, size_t len);
+
+int vault_random_uniform(uint32_t upper_bound, uint32_t *out);
+
+void vault_kdf_params(
+    unsigned long long *opslimit_out,
+    size_t *memlimit_out,
+    int *alg_out
+);
+
+int vault_hash(
+    const unsigned char *in,
+    size_t in_len,
+    unsigned char *out,
+    size_t out_len
+);
+
 void vault_free_buffer(unsigned char *buf);
 
 void vault_secure_zero(void *ptr, size_t len);
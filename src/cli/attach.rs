@@ -0,0 +1,113 @@
+use super::{find_entry, has_flag, master_pwd, opt_value};
+use crate::attachments;
+use crate::models::Vault;
+use crate::storage;
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+const USAGE: &str = "Usage: passlock attach add <entry> <file> [--name <name>]
+       passlock attach get <entry> <name> [-o <file>] [--force]
+       passlock attach rm <entry> <name>
+       passlock attach ls <entry>";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(1);
+}
+
+fn entry_idx(v: &Vault, query: &str) -> Result<usize, String> {
+    let id = find_entry(v, query)?.id.clone();
+    v.e.iter()
+        .position(|e| e.id == id)
+        .ok_or_else(|| "[X] Entry not found".to_string())
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (Some(cmd), Some(query)) = (args.first(), args.get(1)) else {
+        usage();
+    };
+    let pwd = master_pwd()?;
+    let mut vault = storage::ld_vt(&pwd)?;
+    let idx = entry_idx(&vault, query)?;
+
+    match cmd.as_str() {
+        "add" => {
+            let Some(file) = args.get(2) else { usage() };
+            let name = opt_value(args, &["--name"]).map_or_else(
+                || {
+                    Path::new(file)
+                        .file_name()
+                        .map_or(file.clone(), |n| n.to_string_lossy().into_owned())
+                },
+                str::to_string,
+            );
+            if vault.e[idx].attachments.iter().any(|a| a.name == name) {
+                return Err(format!(
+                    "[X] '{}' already has an attachment named '{name}'",
+                    vault.e[idx].n
+                )
+                .into());
+            }
+            let data = fs::read(file)?;
+            let att = attachments::put(&mut vault, &pwd, &name, &data)?;
+            let size = attachments::fmt_size(att.size);
+            let entry = &mut vault.e[idx];
+            entry.attachments.push(att);
+            entry.last_modified = crate::get_timestamp();
+            storage::svv(&vault, &pwd)?;
+            println!("[✔] Attached {name} ({size}) to {}", vault.e[idx].n);
+        }
+        "get" => {
+            let Some(name) = args.get(2) else { usage() };
+            let entry = &vault.e[idx];
+            let att = entry
+                .attachments
+                .iter()
+                .find(|a| &a.name == name)
+                .ok_or_else(|| format!("[X] '{}' has no attachment '{name}'", entry.n))?;
+            let data = attachments::get(&vault, &pwd, att)?;
+            let force = has_flag(args, &["--force"]);
+            match opt_value(args, &["-o", "--out"]) {
+                Some(path) => {
+                    let mut file = OpenOptions::new()
+                        .write(true)
+                        .create(true)
+                        .truncate(true)
+                        .mode(0o600)
+                        .open(path)?;
+                    file.write_all(&data)?;
+                    eprintln!("[✔] Wrote {name} to {path}");
+                }
+                None if io::stdout().is_terminal() && !force => {
+                    return Err(
+                        "[X] Refusing to print an attachment to the terminal (use -o <file> or --force)"
+                            .into(),
+                    );
+                }
+                None => io::stdout().write_all(&data)?,
+            }
+        }
+        "rm" => {
+            let Some(name) = args.get(2) else { usage() };
+            let entry = &mut vault.e[idx];
+            let before = entry.attachments.len();
+            entry.attachments.retain(|a| &a.name != name);
+            if entry.attachments.len() == before {
+                return Err(format!("[X] '{}' has no attachment '{name}'", entry.n).into());
+            }
+            entry.last_modified = crate::get_timestamp();
+            storage::svv(&vault, &pwd)?;
+            attachments::gc(&vault)?;
+            println!("[✔] Removed {name} from {}", vault.e[idx].n);
+        }
+        "ls" => {
+            for a in &vault.e[idx].attachments {
+                println!("{}\t{}", a.name, attachments::fmt_size(a.size));
+            }
+        }
+        _ => usage(),
+    }
+    Ok(())
+}
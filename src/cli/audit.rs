@@ -0,0 +1,73 @@
+use super::{master_pwd, opt_value};
+use crate::audit::{self, DEFAULT_MAX_AGE};
+use crate::breach::{self, Corpus};
+use crate::config;
+use crate::storage;
+use std::path::Path;
+
+const USAGE: &str =
+    "Usage: passlock audit [--max-age <days>] [--breaches <pwned-passwords-sha1.txt|filter>]
+       passlock audit --build-filter <pwned-passwords-sha1.txt> -o <filter> [--fpr <rate>]";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(1);
+}
+
+/// Reports reused, weak, old, breached and otherwise suspect entries. With
+/// `--breaches`, first checks every password against an offline breach
+/// corpus and records the result on the entry. Exits with status 1 when
+/// anything is found.
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(src) = opt_value(args, &["--build-filter"]) {
+        let Some(out) = opt_value(args, &["-o", "--out"]) else {
+            usage()
+        };
+        let fpr: f64 = opt_value(args, &["--fpr"]).map_or(Ok(0.001), str::parse)?;
+        if !(0.0..0.5).contains(&fpr) || fpr == 0.0 {
+            return Err("[X] --fpr must be between 0 and 0.5".into());
+        }
+        let n = breach::build_filter(Path::new(src), Path::new(out), fpr)?;
+        println!("[✔] Wrote a filter of {n} hashes to {out}");
+        return Ok(());
+    }
+    let max_age = match opt_value(args, &["--max-age"]) {
+        Some(d) => d
+            .parse()
+            .map_err(|_| format!("[X] Invalid --max-age '{d}'"))?,
+        None => config::load().audit_max_age.unwrap_or(DEFAULT_MAX_AGE),
+    };
+    let corpus = opt_value(args, &["--breaches"])
+        .map(|p| Corpus::open(Path::new(p)))
+        .transpose()?;
+    let pwd = master_pwd()?;
+    let mut vault = storage::ld_vt(&pwd)?;
+
+    if let Some(corpus) = corpus {
+        for e in vault.e.iter_mut().filter(|e| !e.p.is_empty()) {
+            e.breached = corpus.lookup(&e.p)?;
+        }
+        storage::svv(&vault, &pwd)?;
+    }
+
+    let findings = audit::run(&vault, crate::get_timestamp(), max_age);
+    let mut last = None;
+    for f in &findings {
+        if last != Some(f.kind) {
+            let n = findings.iter().filter(|g| g.kind == f.kind).count();
+            println!("== {} ({n}) ==", f.kind.label());
+            last = Some(f.kind);
+        }
+        if f.detail.is_empty() {
+            println!("{}", f.path);
+        } else {
+            println!("{}\t{}", f.path, f.detail);
+        }
+    }
+    if findings.is_empty() {
+        println!("[✔] No problems found");
+    } else {
+        std::process::exit(1);
+    }
+    Ok(())
+}
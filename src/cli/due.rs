@@ -0,0 +1,85 @@
+use super::{find_entry, master_pwd};
+use crate::storage;
+
+const USAGE: &str = "Usage: passlock due
+       passlock due set <entry> <days|off>
+       passlock due set-tag <tag> <days|off>";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(1);
+}
+
+fn parse_days(s: &str) -> Option<u32> {
+    match s {
+        "off" | "0" => None,
+        _ => Some(s.parse().unwrap_or_else(|_| usage())),
+    }
+}
+
+/// Lists passwords due for rotation. Exits with status 1 when any are
+/// overdue so it can nag from cron.
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let pwd = master_pwd()?;
+    let mut vault = storage::ld_vt(&pwd)?;
+
+    match args.first().map(String::as_str) {
+        None => {}
+        Some("set") => {
+            let [_, query, days] = args else { usage() };
+            let id = find_entry(&vault, query)?.id.clone();
+            let days = parse_days(days);
+            if let Some(e) = vault.e.iter_mut().find(|e| e.id == id) {
+                e.rotate_days = days;
+                e.last_modified = crate::get_timestamp();
+            }
+            storage::svv(&vault, &pwd)?;
+            match days {
+                Some(_) => println!("[✔] Rotation for {query} set to {}", fmt_days(days)),
+                None => println!("[✔] {query} now follows its tags' rotation"),
+            }
+            return Ok(());
+        }
+        Some("set-tag") => {
+            let [_, tag, days] = args else { usage() };
+            let tag = tag.trim().to_lowercase();
+            let days = parse_days(days);
+            match days {
+                Some(d) => vault.tag_rotation.insert(tag.clone(), d),
+                None => vault.tag_rotation.remove(&tag),
+            };
+            storage::svv(&vault, &pwd)?;
+            println!("[✔] Rotation for tag {tag} set to {}", fmt_days(days));
+            return Ok(());
+        }
+        Some(_) => usage(),
+    }
+
+    let due = vault.due(crate::get_timestamp());
+    let mut overdue = false;
+    for (e, days) in &due {
+        let status = match *days {
+            d if d < 0 => {
+                overdue = true;
+                format!("EXPIRED {} days ago", -d)
+            }
+            0 => {
+                overdue = true;
+                "EXPIRES today".to_string()
+            }
+            d => format!("due in {d} days"),
+        };
+        println!("{}\t{status}", e.path());
+    }
+    if due.is_empty() {
+        println!("[✔] Nothing due for rotation");
+    }
+    if overdue {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn fmt_days(days: Option<u32>) -> String {
+    days.map_or("off".to_string(), |d| format!("every {d} days"))
+}
@@ -0,0 +1,442 @@
+use super::{has_flag, iso8601, opt_value, prompt_pwd};
+use crate::models::{Entry, EntryKind, FieldKind, HistField, Vault};
+use crate::storage;
+use serde_json::{json, Value};
+use std::fs::{self, OpenOptions, Permissions};
+use std::io::{self, IsTerminal, Write};
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+const USAGE: &str =
+    "Usage: passlock export --to csv|json|bitwarden-json [-o <file>] [--history] [--tags] [--force]";
+
+#[derive(Clone, Copy)]
+enum Format {
+    Csv,
+    Json,
+    Bitwarden,
+}
+
+struct ExportOpts {
+    history: bool,
+    tags: bool,
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let format = match opt_value(args, &["--to"]) {
+        Some("csv") => Format::Csv,
+        Some("json") => Format::Json,
+        Some("bitwarden-json") => Format::Bitwarden,
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }
+    };
+    let out_path = opt_value(args, &["-o", "--out"]);
+    let force = has_flag(args, &["--force"]);
+    let opts = ExportOpts {
+        history: has_flag(args, &["--history"]),
+        tags: has_flag(args, &["--tags"]),
+    };
+
+    if out_path.is_none() && io::stdout().is_terminal() && !force {
+        return Err(
+            "[X] Refusing to print a plaintext export to the terminal (use -o <file> or --force)"
+                .into(),
+        );
+    }
+
+    // Always ask again, even if the vault was unlocked moments ago: this is
+    // the only way secrets leave the vault unencrypted.
+    let pwd = prompt_pwd("Re-enter master password to export: ")?;
+    let vault = storage::ld_vt(&pwd)?;
+
+    let data = match format {
+        Format::Csv => to_csv(&vault, &opts),
+        Format::Json => serde_json::to_string_pretty(&to_json(&vault, &opts))?,
+        Format::Bitwarden => serde_json::to_string_pretty(&to_bitwarden(&vault, &opts))?,
+    };
+
+    match out_path {
+        Some(path) => {
+            let mut file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .mode(0o600)
+                .open(path)?;
+            if file.is_terminal() && !force {
+                return Err(
+                    "[X] Refusing to write a plaintext export to a terminal (use --force)".into(),
+                );
+            }
+            // `mode` only applies when the file is created, so tighten an
+            // existing file explicitly.
+            fs::set_permissions(path, Permissions::from_mode(0o600))?;
+            file.write_all(data.as_bytes())?;
+            eprintln!("[✔] Exported {} entries to {path}", vault.e.len());
+        }
+        None => {
+            io::stdout().write_all(data.as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn csv_history(e: &Entry) -> String {
+    e.history
+        .iter()
+        .map(|h| {
+            format!(
+                "{}: {} ({})",
+                h.field.label(),
+                h.password,
+                iso8601(h.changed_at)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Typed data and custom fields flattened into one `name=value; ...` cell.
+fn csv_fields(e: &Entry) -> String {
+    e.data
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .chain(e.fields.iter().map(|f| format!("{}={}", f.name, f.value)))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn to_csv(v: &Vault, opts: &ExportOpts) -> String {
+    let mut header = vec![
+        "name", "type", "username", "password", "url", "notes", "fields", "created", "modified",
+    ];
+    if opts.tags {
+        header.push("tags");
+    }
+    if opts.history {
+        header.push("history");
+    }
+    let mut out = header.join(",");
+    out.push('\n');
+    for e in &v.e {
+        let mut row = vec![
+            csv_field(&e.n),
+            e.kind.label().to_string(),
+            csv_field(&e.u),
+            csv_field(&e.p),
+            csv_field(e.url.as_deref().unwrap_or_default()),
+            csv_field(e.nt.as_deref().unwrap_or_default()),
+            csv_field(&csv_fields(e)),
+            iso8601(e.t),
+            iso8601(e.last_modified),
+        ];
+        if opts.tags {
+            row.push(csv_field(&e.tags.join(";")));
+        }
+        if opts.history {
+            row.push(csv_field(&csv_history(e)));
+        }
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn to_json(v: &Vault, opts: &ExportOpts) -> Value {
+    let mut entries = Vec::with_capacity(v.e.len());
+    for e in &v.e {
+        let mut item = json!({
+            "id": e.id,
+            "name": e.n,
+            "username": e.u,
+            "password": e.p,
+            "url": e.url,
+            "notes": e.nt,
+            "type": e.kind,
+            "data": e.data,
+            "otp": e.otp,
+            "fields": e.fields,
+            "created": iso8601(e.t),
+            "modified": iso8601(e.last_modified),
+        });
+        if opts.tags {
+            item["tags"] = json!(e.tags);
+        }
+        if opts.history {
+            let history: Vec<Value> = e
+                .history
+                .iter()
+                .map(|h| {
+                    json!({
+                        "field": h.field,
+                        "password": h.password,
+                        "changed_at": iso8601(h.changed_at),
+                    })
+                })
+                .collect();
+            item["history"] = json!(history);
+        }
+        entries.push(item);
+    }
+    json!({
+        "exported_at": iso8601(crate::get_timestamp()),
+        "entries": entries,
+    })
+}
+
+/// Builds an unencrypted Bitwarden JSON export. Bitwarden has no tags, so
+/// they travel as a text custom field.
+fn to_bitwarden(v: &Vault, opts: &ExportOpts) -> Value {
+    let items: Vec<Value> = v
+        .e
+        .iter()
+        .map(|e| {
+            let mut fields: Vec<Value> = e
+                .fields
+                .iter()
+                .map(|f| {
+                    let kind = if f.kind.is_secret() { 1 } else { 0 };
+                    json!({ "name": f.name, "value": f.value, "type": kind })
+                })
+                .collect();
+            let totp = e.otp.clone().or_else(|| {
+                e.fields
+                    .iter()
+                    .find(|f| f.kind == FieldKind::Totp)
+                    .map(|f| f.value.clone())
+            });
+            if opts.tags && !e.tags.is_empty() {
+                fields.push(json!({ "name": "tags", "value": e.tags.join(","), "type": 0 }));
+            }
+            let uris: Vec<Value> = e
+                .url
+                .iter()
+                .map(|u| json!({ "match": null, "uri": u }))
+                .collect();
+            let (bw_type, section, body) = match e.kind {
+                EntryKind::Login => (
+                    1,
+                    "login",
+                    json!({ "uris": uris, "username": e.u, "password": e.p, "totp": totp }),
+                ),
+                EntryKind::Card => {
+                    let (month, year) = e.get_field("expiry").split_once('/').unwrap_or_default();
+                    let year = if year.len() == 2 {
+                        format!("20{year}")
+                    } else {
+                        year.to_string()
+                    };
+                    (
+                        3,
+                        "card",
+                        json!({
+                            "cardholderName": e.get_field("holder"),
+                            "brand": null,
+                            "number": e.get_field("number"),
+                            "expMonth": month.trim_start_matches('0'),
+                            "expYear": year,
+                            "code": e.get_field("cvv"),
+                        }),
+                    )
+                }
+                EntryKind::Identity => {
+                    let full = e.get_field("full_name");
+                    let (first, last) = full.rsplit_once(' ').unwrap_or((full, ""));
+                    (
+                        4,
+                        "identity",
+                        json!({
+                            "firstName": first,
+                            "lastName": last,
+                            "address1": e.get_field("address"),
+                            "phone": e.get_field("phone"),
+                        }),
+                    )
+                }
+                EntryKind::SshKey => {
+                    if !e.p.is_empty() {
+                        fields.push(json!({ "name": "passphrase", "value": e.p, "type": 1 }));
+                    }
+                    (
+                        5,
+                        "sshKey",
+                        json!({
+                            "privateKey": e.get_field("private_key"),
+                            "publicKey": e.get_field("public_key"),
+                            "keyFingerprint": "",
+                        }),
+                    )
+                }
+                // Bitwarden has no Wi-Fi type; keep the network details as
+                // fields on a secure note.
+                EntryKind::Note | EntryKind::Wifi => {
+                    for sf in e.kind.schema().iter().filter(|sf| sf.key != "nt") {
+                        let kind = if sf.secret { 1 } else { 0 };
+                        fields.push(json!({ "name": sf.label, "value": e.get_field(sf.key), "type": kind }));
+                    }
+                    (2, "secureNote", json!({ "type": 0 }))
+                }
+            };
+            let mut item = json!({
+                "id": e.id,
+                "organizationId": null,
+                "folderId": null,
+                "type": bw_type,
+                "reprompt": 0,
+                "name": e.n,
+                "notes": e.nt,
+                "favorite": e.favorite,
+                "fields": fields,
+                "collectionIds": null,
+                "creationDate": iso8601(e.t),
+                "revisionDate": iso8601(e.last_modified),
+            });
+            item[section] = body;
+            if opts.history {
+                item["passwordHistory"] = e
+                    .history
+                    .iter()
+                    .filter(|h| h.field == HistField::Password)
+                    .map(|h| json!({ "lastUsedDate": iso8601(h.changed_at), "password": h.password }))
+                    .collect();
+            }
+            item
+        })
+        .collect();
+    json!({
+        "encrypted": false,
+        "folders": [],
+        "items": items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault() -> Vault {
+        let mut v = Vault::new(String::new());
+        v.e.push(
+            serde_json::from_value(json!({
+                "id": "a", "n": "Mail, work", "u": "me", "p": "p\"w", "t": 0,
+                "url": "https://mail.example", "tags": ["work", "mail"],
+                "fields": [
+                    { "name": "pin", "value": "1234", "kind": "hidden" },
+                    { "name": "note", "value": "x", "kind": "text" },
+                ],
+                "history": [
+                    { "field": "password", "password": "old", "changed_at": 0 },
+                    { "field": "username", "password": "me2", "changed_at": 0 },
+                ],
+            }))
+            .unwrap(),
+        );
+        v.e.push(
+            serde_json::from_value(json!({
+                "id": "b", "n": "Visa", "u": "", "p": "", "t": 0, "kind": "card",
+                "data": { "holder": "A B", "number": "4111111111111111", "expiry": "03/29" },
+            }))
+            .unwrap(),
+        );
+        v
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("a\nb"), "\"a\nb\"");
+        assert_eq!(csv_field("a\rb"), "\"a\rb\"");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn test_csv() {
+        let opts = ExportOpts {
+            history: true,
+            tags: true,
+        };
+        let csv = to_csv(&vault(), &opts);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("name,type,username,password,url,notes,fields,created,modified,tags,history")
+        );
+        let row = lines.next().unwrap();
+        assert!(row.starts_with("\"Mail, work\",Login,me,\"p\"\"w\",https://mail.example,,"));
+        assert!(row.contains("pin=1234; note=x"));
+        assert!(row.ends_with(
+            ",work;mail,Password: old (1970-01-01T00:00:00Z); Username: me2 (1970-01-01T00:00:00Z)"
+        ));
+        assert_eq!(lines.count(), 1);
+    }
+
+    #[test]
+    fn test_json() {
+        let plain = ExportOpts {
+            history: false,
+            tags: false,
+        };
+        let out = to_json(&vault(), &plain);
+        let entries = out["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["name"], "Mail, work");
+        assert_eq!(entries[0]["password"], "p\"w");
+        assert_eq!(entries[0]["fields"][0]["kind"], "hidden");
+        assert_eq!(entries[1]["type"], "card");
+        assert_eq!(entries[1]["data"]["number"], "4111111111111111");
+        assert!(entries[0].get("tags").is_none());
+        assert!(entries[0].get("history").is_none());
+
+        let full = ExportOpts {
+            history: true,
+            tags: true,
+        };
+        let out = to_json(&vault(), &full);
+        assert_eq!(out["entries"][0]["tags"], json!(["work", "mail"]));
+        assert_eq!(out["entries"][0]["history"][1]["field"], "username");
+    }
+
+    #[test]
+    fn test_bitwarden() {
+        let opts = ExportOpts {
+            history: true,
+            tags: true,
+        };
+        let out = to_bitwarden(&vault(), &opts);
+        assert_eq!(out["encrypted"], false);
+        let login = &out["items"][0];
+        assert_eq!(login["type"], 1);
+        assert_eq!(login["login"]["username"], "me");
+        assert_eq!(login["login"]["uris"][0]["uri"], "https://mail.example");
+        assert_eq!(
+            login["fields"],
+            json!([
+                { "name": "pin", "value": "1234", "type": 1 },
+                { "name": "note", "value": "x", "type": 0 },
+                { "name": "tags", "value": "work,mail", "type": 0 },
+            ])
+        );
+        // Only password changes go into Bitwarden's history.
+        assert_eq!(
+            login["passwordHistory"],
+            json!([{ "lastUsedDate": "1970-01-01T00:00:00Z", "password": "old" }])
+        );
+        let card = &out["items"][1];
+        assert_eq!(card["type"], 3);
+        assert_eq!(card["card"]["expMonth"], "3");
+        assert_eq!(card["card"]["expYear"], "2029");
+        assert_eq!(card["card"]["cardholderName"], "A B");
+        assert!(card.get("login").is_none());
+    }
+}
@@ -0,0 +1,37 @@
+use super::master_pwd;
+use crate::storage;
+
+const USAGE: &str = "Usage: passlock folders [mv <from> <to>]";
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let pwd = master_pwd()?;
+    let mut vault = storage::ld_vt(&pwd)?;
+    match args.first().map(String::as_str) {
+        None => {
+            for folder in vault.folders() {
+                let depth = folder.matches('/').count();
+                let name = folder.rsplit('/').next().unwrap_or(&folder);
+                let count = vault
+                    .e
+                    .iter()
+                    .filter(|e| e.folder.as_deref() == Some(folder.as_str()))
+                    .count();
+                println!("{}{name}/  ({count})", "  ".repeat(depth));
+            }
+        }
+        Some("mv") => {
+            let (Some(from), Some(to)) = (args.get(1), args.get(2)) else {
+                eprintln!("{USAGE}");
+                std::process::exit(1);
+            };
+            let moved = vault.move_folder(from, to)?;
+            storage::svv(&vault, &pwd)?;
+            println!("[✔] Moved {moved} entries from {from} to {to}");
+        }
+        Some(_) => {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use super::{find_entry, has_flag, master_pwd, opt_value};
+use crate::crypto;
+use crate::models::HistField;
+use crate::policy::Policy;
+use crate::storage;
+
+const USAGE: &str = "Usage: passlock generate [<options>] [--count <n>]
+       passlock generate --for <entry> [<options>] [--save]
+       passlock policy <entry> [<options> | --clear]
+Options: --length <n> --no-lower --no-upper --no-digits --no-symbols
+         --min-lower <n> --min-upper <n> --min-digits <n> --min-symbols <n>
+         --symbols <set> --no-ambiguous --no-repeat
+         --words <n> --separator <s> --capitalize --insert
+         --pronounceable --pattern <template>
+Patterns: c/C consonant  v/V vowel  a/A letter  d or 9 digit  s symbol
+          x letter or digit  [A-F0-9] set  {n} repeat  \\ escape";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(1);
+}
+
+/// Generates passwords. With `--for` the entry's saved rules are the
+/// starting point and `--save` makes the result its new password.
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut policy = Policy::default();
+    let target = opt_value(args, &["--for"]);
+    let mut vault = None;
+    let mut pwd = String::new();
+    if let Some(query) = target {
+        pwd = master_pwd()?;
+        let v = storage::ld_vt(&pwd)?;
+        if let Some(ref p) = find_entry(&v, query)?.policy {
+            policy = p.clone();
+        }
+        vault = Some(v);
+    }
+    let rest = policy.apply_args(args)?;
+    let mut count = 1;
+    let mut it = rest.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--count" => {
+                count = it
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or("[X] --count needs a number")?;
+            }
+            "--for" => {
+                it.next();
+            }
+            "--save" if target.is_some() => {}
+            _ => usage(),
+        }
+    }
+
+    let save = has_flag(args, &["--save"]);
+    if let (Some(mut v), Some(query), true) = (vault, target, save) {
+        let new = crypto::gen_pwd(&policy).map_err(|e| format!("[X] {e}"))?;
+        let id = find_entry(&v, query)?.id.clone();
+        let depth = v.history_depth;
+        let Some(entry) = v.e.iter_mut().find(|e| e.id == id) else {
+            return Err("[X] Entry not found".into());
+        };
+        let old = std::mem::replace(&mut entry.p, new);
+        entry.push_history(HistField::Password, old, depth, false);
+        entry.last_modified = crate::get_timestamp();
+        let name = entry.n.clone();
+        storage::svv(&v, &pwd)?;
+        println!("[✔] New password saved for {name}");
+        return Ok(());
+    }
+    for _ in 0..count {
+        println!(
+            "{}",
+            crypto::gen_pwd(&policy).map_err(|e| format!("[X] {e}"))?
+        );
+    }
+    Ok(())
+}
+
+/// Shows, sets or clears the generator rules saved on an entry. Options
+/// are applied on top of the current rules.
+pub fn policy(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(query) = args.first() else { usage() };
+    let pwd = master_pwd()?;
+    let mut vault = storage::ld_vt(&pwd)?;
+    let id = find_entry(&vault, query)?.id.clone();
+    let Some(entry) = vault.e.iter_mut().find(|e| e.id == id) else {
+        return Err("[X] Entry not found".into());
+    };
+    let opts = &args[1..];
+    if opts.is_empty() {
+        match entry.policy {
+            Some(ref p) => println!("{}", p.summary()),
+            None => println!("No rules saved for {}", entry.n),
+        }
+        return Ok(());
+    }
+    if has_flag(opts, &["--clear"]) {
+        entry.policy = None;
+        println!("[✔] Removed the rules for {}", entry.n);
+    } else {
+        let mut policy = entry.policy.clone().unwrap_or_default();
+        if !policy.apply_args(opts)?.is_empty() {
+            usage();
+        }
+        policy.validate().map_err(|e| format!("[X] {e}"))?;
+        println!("[✔] Rules for {}: {}", entry.n, policy.summary());
+        entry.policy = Some(policy);
+    }
+    entry.last_modified = crate::get_timestamp();
+    storage::svv(&vault, &pwd)?;
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use super::{find_entry, has_flag, master_pwd, opt_value};
+use crate::clipboard;
+use crate::config;
+use crate::models::Entry;
+use crate::storage;
+use std::io::{Read, Write};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+
+const USAGE: &str = "Usage: passlock get <entry> [--field <name>] [--clip]";
+
+/// Resolves `--field`: custom fields first, then the entry type's schema,
+/// then the built-in columns.
+fn field_value(e: &Entry, name: &str) -> Option<String> {
+    if let Some(f) = e.fields.iter().find(|f| f.name.eq_ignore_ascii_case(name)) {
+        return Some(f.value.clone());
+    }
+    if let Some(sf) = e
+        .kind
+        .schema()
+        .iter()
+        .find(|sf| sf.key.eq_ignore_ascii_case(name) || sf.label.eq_ignore_ascii_case(name))
+    {
+        return Some(e.get_field(sf.key).to_string());
+    }
+    match name.to_lowercase().as_str() {
+        "name" => Some(e.n.clone()),
+        "username" | "user" => Some(e.u.clone()),
+        "password" | "pass" => Some(e.p.clone()),
+        "url" => e.url.clone(),
+        "notes" => e.nt.clone(),
+        _ => None,
+    }
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(query) = args.first().filter(|a| !a.starts_with("--")) else {
+        eprintln!("{USAGE}");
+        std::process::exit(1);
+    };
+    let field = opt_value(args, &["--field", "-f"]);
+
+    let pwd = master_pwd()?;
+    let vault = storage::ld_vt(&pwd)?;
+    let entry = find_entry(&vault, query)?;
+    let value = match field {
+        Some(name) => field_value(entry, name)
+            .ok_or_else(|| format!("[X] '{}' has no field '{name}'", entry.n))?,
+        None => entry.p.clone(),
+    };
+    if has_flag(args, &["--clip", "-c"]) {
+        clipboard::copy(&value).map_err(|e| format!("[X] {e}"))?;
+        let secs = config::load().clip_secs();
+        if secs > 0 {
+            spawn_clear(&value, secs)?;
+            println!("[✔] Copied to the clipboard, clearing in {secs}s");
+        } else {
+            println!("[✔] Copied to the clipboard");
+        }
+    } else {
+        println!("{value}");
+    }
+    // A read does not re-encrypt the vault just to bump the use counter;
+    // only the TUI counts uses.
+    Ok(())
+}
+
+/// Hands the delayed clear to a detached `passlock __clip-clear`, so the
+/// command returns at once. Only the digest of the value is passed on, over
+/// stdin rather than the argument list.
+fn spawn_clear(value: &str, secs: u64) -> Result<(), Box<dyn std::error::Error>> {
+    let mut child = Command::new(std::env::current_exe()?)
+        .args(["__clip-clear", &secs.to_string()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(hex::encode(clipboard::tag(value)).as_bytes())?;
+    }
+    Ok(())
+}
+
+/// The detached half of `get --clip`: waits, then clears the clipboard if
+/// it still holds the copied value.
+pub fn clip_clear(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let secs: u64 = args.first().and_then(|s| s.parse().ok()).unwrap_or(0);
+    let mut digest = String::new();
+    std::io::stdin().read_to_string(&mut digest)?;
+    let tag: clipboard::Tag = hex::decode(digest.trim())?
+        .try_into()
+        .map_err(|_| "bad digest")?;
+    std::thread::sleep(std::time::Duration::from_secs(secs));
+    clipboard::clear_if(&tag)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_value() {
+        let e: Entry = serde_json::from_value(serde_json::json!({
+            "id": "1", "n": "Visa", "u": "", "p": "", "t": 0, "kind": "card",
+            "data": { "number": "4111111111111111", "cvv": "123" },
+            "fields": [
+                { "name": "PIN", "value": "0000", "kind": "hidden" },
+                { "name": "cvv", "value": "override" },
+            ],
+        }))
+        .unwrap();
+        assert_eq!(field_value(&e, "pin").as_deref(), Some("0000"));
+        // Custom fields win over schema fields of the same name.
+        assert_eq!(field_value(&e, "CVV").as_deref(), Some("override"));
+        assert_eq!(
+            field_value(&e, "number").as_deref(),
+            Some("4111111111111111")
+        );
+        assert_eq!(field_value(&e, "name").as_deref(), Some("Visa"));
+        assert_eq!(field_value(&e, "url"), None);
+        assert_eq!(field_value(&e, "nope"), None);
+    }
+}
@@ -0,0 +1,76 @@
+use super::{find_entry, has_flag, iso8601, master_pwd};
+use crate::models::HistField;
+use crate::storage;
+
+const USAGE: &str = "Usage: passlock history <entry> [--show]
+       passlock history restore <entry> <n>
+       passlock history depth [<n>]";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(1);
+}
+
+/// Lists or restores old values of an entry. Records are numbered newest
+/// first, matching the TUI history screen.
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(first) = args.first() else { usage() };
+    let pwd = master_pwd()?;
+    let mut vault = storage::ld_vt(&pwd)?;
+
+    match first.as_str() {
+        "depth" => match args.get(1) {
+            None => println!("{}", vault.history_depth),
+            Some(n) => {
+                let depth: usize = n.parse().map_err(|_| format!("[X] Invalid depth '{n}'"))?;
+                vault.history_depth = depth;
+                for e in &mut vault.e {
+                    e.trim_history(depth);
+                }
+                storage::svv(&vault, &pwd)?;
+                println!("[✔] Keeping the last {depth} changes per field");
+            }
+        },
+        "restore" => {
+            let (Some(query), Some(n)) = (args.get(1), args.get(2)) else {
+                usage()
+            };
+            let depth = vault.history_depth;
+            let id = find_entry(&vault, query)?.id.clone();
+            let Some(entry) = vault.e.iter_mut().find(|e| e.id == id) else {
+                return Err("[X] Entry not found".into());
+            };
+            let len = entry.history.len();
+            let idx = n
+                .parse::<usize>()
+                .ok()
+                .filter(|n| (1..=len).contains(n))
+                .map(|n| len - n)
+                .ok_or_else(|| format!("[X] '{}' has no history record {n}", entry.n))?;
+            let field = entry.history[idx].field;
+            entry.restore_history(idx, depth)?;
+            let name = entry.n.clone();
+            storage::svv(&vault, &pwd)?;
+            println!("[✔] Restored {} of {name}", field.label().to_lowercase());
+        }
+        query => {
+            let entry = find_entry(&vault, query)?;
+            let show = has_flag(args, &["--show"]);
+            for (i, h) in entry.history.iter().rev().enumerate() {
+                let value = if show || h.field != HistField::Password {
+                    h.password.as_str()
+                } else {
+                    "********"
+                };
+                let restore = if h.restore { "\t(restore)" } else { "" };
+                println!(
+                    "{}\t{}\t{}\t{value}{restore}",
+                    i + 1,
+                    iso8601(h.changed_at),
+                    h.field.label()
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use super::{iso8601, opt_value, prompt_pwd};
+use crate::storage;
+use crate::vault_ffi;
+use std::fmt::Write as _;
+use std::fs;
+
+const USAGE: &str = "Usage: passlock emergency-kit -o <kit.html>";
+
+const STYLE: &str = "body{font-family:Georgia,serif;max-width:720px;margin:40px auto;color:#222}\
+h1{border-bottom:3px solid #222;padding-bottom:8px}\
+table{border-collapse:collapse;width:100%;margin:16px 0}\
+td{border:1px solid #999;padding:8px;vertical-align:top}\
+td.k{width:35%;font-weight:bold;background:#f2f2f2}\
+code{font-family:monospace;font-size:1.05em}\
+.write{height:48px;border:2px dashed #666;margin:8px 0 24px}\
+.note{font-size:.9em;color:#555}\
+@media print{body{margin:0}}";
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Short, human-comparable fingerprint of the vault salt.
+fn salt_fp(salt: &[u8]) -> Result<String, String> {
+    let digest = vault_ffi::hash(salt, 16)?;
+    let hex = hex::encode(digest).to_uppercase();
+    Ok(hex
+        .as_bytes()
+        .chunks(4)
+        .map(|c| String::from_utf8_lossy(c).into_owned())
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(out_path) = opt_value(args, &["-o", "--out"]) else {
+        eprintln!("{USAGE}");
+        std::process::exit(1);
+    };
+
+    let header = storage::rd_hdr()?;
+    let pwd = prompt_pwd("Master password: ")?;
+    // Only the salt and creation time are needed; entries are never built.
+    let vault = storage::ld_meta(&pwd)?;
+    if hex::encode(&header.salt) != vault.s {
+        return Err("[X] Vault header salt does not match vault metadata".into());
+    }
+
+    let kdf = vault_ffi::kdf_params();
+    let created = if vault.created == 0 {
+        "Unknown (vault predates creation tracking)".to_string()
+    } else {
+        iso8601(vault.created)[..10].to_string()
+    };
+    let location = storage::vt_p().display().to_string();
+
+    let rows = [
+        ("Vault location", html_escape(&location)),
+        ("Created", created),
+        (
+            "Salt fingerprint",
+            format!("<code>{}</code>", salt_fp(&header.salt)?),
+        ),
+        (
+            "Key derivation",
+            format!(
+                "{}, opslimit {}, memlimit {} MiB",
+                kdf.alg_name(),
+                kdf.opslimit,
+                kdf.memlimit / (1024 * 1024)
+            ),
+        ),
+        ("Cipher", "ChaCha20-Poly1305 (IETF)".to_string()),
+        (
+            "Kit generated",
+            iso8601(crate::get_timestamp())[..10].to_string(),
+        ),
+    ];
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>PASSLOCK Emergency Kit</title><style>{STYLE}</style></head><body>\n"
+    );
+    html.push_str("<h1>PASSLOCK Emergency Kit</h1>\n");
+    html.push_str("<p>Print this page and store it somewhere safe and offline. Anyone holding both this sheet and a copy of the vault file can open your vault.</p>\n<table>\n");
+    for (k, v) in rows {
+        let _ = writeln!(html, "<tr><td class=\"k\">{k}</td><td>{v}</td></tr>");
+    }
+    html.push_str("</table>\n<h2>Master password</h2>\n<div class=\"write\"></div>\n");
+    html.push_str("<p class=\"note\">The master password is never stored anywhere. If it is lost, the vault cannot be recovered. The salt fingerprint lets you confirm this sheet belongs to the vault file you are restoring.</p>\n");
+    html.push_str("</body></html>\n");
+
+    fs::write(out_path, html)?;
+    println!("[✔] Emergency kit written to {out_path}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_escape() {
+        assert_eq!(html_escape("plain"), "plain");
+        assert_eq!(
+            html_escape("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+        // `&` goes first so entities are not escaped twice.
+        assert_eq!(html_escape("&lt;"), "&amp;lt;");
+    }
+
+    #[test]
+    fn test_salt_fp() {
+        vault_ffi::init().unwrap();
+        let fp = salt_fp(&[7u8; vault_ffi::SALT_LENGTH]).unwrap();
+        assert_eq!(fp.len(), 39);
+        assert!(fp.split(' ').all(|g| g.len() == 4
+            && g.chars()
+                .all(|c| c.is_ascii_hexdigit() && !c.is_ascii_lowercase())));
+        assert_eq!(fp, salt_fp(&[7u8; vault_ffi::SALT_LENGTH]).unwrap());
+        assert_ne!(fp, salt_fp(&[8u8; vault_ffi::SALT_LENGTH]).unwrap());
+    }
+}
@@ -0,0 +1,25 @@
+use super::{master_pwd, opt_value};
+use crate::config;
+use crate::models::SortMode;
+use crate::storage;
+
+const USAGE: &str = "Usage: passlock list [--sort name|modified|created|age|used]";
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let sort = match opt_value(args, &["--sort"]) {
+        Some(s) => SortMode::parse(s).unwrap_or_else(|| {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }),
+        None => config::load().sort,
+    };
+
+    let vault = storage::ld_vt(&master_pwd()?)?;
+    let mut entries = vault.e;
+    sort.apply(&mut entries);
+    for e in &entries {
+        let star = if e.favorite { "★ " } else { "  " };
+        println!("{star}{}\t{}", e.path(), e.u);
+    }
+    Ok(())
+}
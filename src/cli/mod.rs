@@ -0,0 +1,149 @@
+pub mod attach;
+pub mod audit;
+pub mod due;
+pub mod export;
+pub mod folders;
+pub mod generate;
+pub mod get;
+pub mod history;
+pub mod kit;
+pub mod list;
+pub mod strength;
+pub mod tags;
+pub mod totp;
+pub mod trash;
+
+use crate::models::{norm_folder, Entry, Vault};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::io::AsRawFd;
+
+/// Returns the value following any of `names` in `args`, e.g. `--to csv`.
+pub fn opt_value<'a>(args: &'a [String], names: &[&str]) -> Option<&'a str> {
+    args.iter()
+        .position(|a| names.contains(&a.as_str()))
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+pub fn has_flag(args: &[String], names: &[&str]) -> bool {
+    args.iter().any(|a| names.contains(&a.as_str()))
+}
+
+/// Prompts for a password on the controlling terminal with echo disabled.
+///
+/// Reads from `/dev/tty` rather than stdin so that piping a command's output
+/// never swallows the password prompt.
+pub fn prompt_pwd(prompt: &str) -> Result<String, String> {
+    let tty = File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .map_err(|_| "no terminal available for password prompt")?;
+    let fd = tty.as_raw_fd();
+
+    let mut term: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(fd, &raw mut term) } != 0 {
+        return Err("failed to read terminal attributes".to_string());
+    }
+    let saved = term;
+    term.c_lflag &= !libc::ECHO;
+    term.c_lflag |= libc::ECHONL;
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw const term) };
+
+    let mut out = &tty;
+    let _ = write!(out, "{prompt}");
+    let _ = out.flush();
+    let mut line = String::new();
+    let res = BufReader::new(&tty).read_line(&mut line);
+
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw const saved) };
+    res.map_err(|e| e.to_string())?;
+
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Asks a yes/no question on the controlling terminal. Anything but `y` is
+/// a no.
+pub fn confirm(prompt: &str) -> Result<bool, String> {
+    let tty = File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .map_err(|_| "no terminal available for confirmation (use --yes)")?;
+    let mut out = &tty;
+    let _ = write!(out, "{prompt} [y/N] ");
+    let _ = out.flush();
+    let mut line = String::new();
+    BufReader::new(&tty)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+    Ok(line.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Master password for non-interactive use: `PASSLOCK_PASSWORD` if set,
+/// otherwise a terminal prompt.
+pub fn master_pwd() -> Result<String, String> {
+    match std::env::var("PASSLOCK_PASSWORD") {
+        Ok(pwd) if !pwd.is_empty() => Ok(pwd),
+        _ => prompt_pwd("Master password: "),
+    }
+}
+
+/// Looks an entry up by id, then by case-insensitive name, then by
+/// `folder/name` path such as `work/aws/prod-root`.
+pub fn find_entry<'a>(v: &'a Vault, query: &str) -> Result<&'a Entry, String> {
+    if let Some(e) = v.e.iter().find(|e| e.id == query) {
+        return Ok(e);
+    }
+    let q = query.to_lowercase();
+    let mut matches: Vec<&Entry> = v.e.iter().filter(|e| e.n.to_lowercase() == q).collect();
+    if matches.is_empty() {
+        if let Some((folder, name)) = query.rsplit_once('/') {
+            let folder = norm_folder(folder);
+            matches =
+                v.e.iter()
+                    .filter(|e| e.folder == folder && e.n.eq_ignore_ascii_case(name))
+                    .collect();
+        }
+    }
+    match matches.as_slice() {
+        [] => Err(format!("[X] No entry named '{query}'")),
+        [e] => Ok(e),
+        _ => Err(format!(
+            "[X] '{query}' is ambiguous, use a path or id: {}",
+            matches
+                .iter()
+                .map(|e| format!("{} ({})", e.path(), e.id))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Formats a unix timestamp as an RFC 3339 UTC string.
+pub fn iso8601(ts: u64) -> String {
+    let (y, m, d) = civil_date(ts / 86400);
+    let secs = ts % 86400;
+    format!(
+        "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+/// Converts days since the unix epoch into a (year, month, day) triple.
+#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+fn civil_date(days: u64) -> (i64, u64, u64) {
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe as i64 + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
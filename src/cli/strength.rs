@@ -0,0 +1,26 @@
+use crate::crypto;
+use std::io::{self, BufRead};
+
+const USAGE: &str = "Usage: passlock strength [--input <word>]... < password";
+
+/// Rates a password read from stdin (never argv, which other users can see)
+/// and prints the result as JSON. The web API shells out to this.
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut inputs = Vec::new();
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match (arg.as_str(), rest.next()) {
+            ("--input", Some(word)) => inputs.push(word.as_str()),
+            _ => {
+                eprintln!("{USAGE}");
+                std::process::exit(1);
+            }
+        }
+    }
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    let password = line.trim_end_matches(['\r', '\n']);
+    let result = crypto::calc_pwd_strength(password, &inputs);
+    println!("{}", serde_json::to_string(&result)?);
+    Ok(())
+}
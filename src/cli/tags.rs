@@ -0,0 +1,85 @@
+use super::{confirm, has_flag, master_pwd};
+use crate::storage;
+
+const USAGE: &str = "Usage: passlock tags
+       passlock tags rename <old> <new> [--yes]
+       passlock tags merge <tag>... <into> [--yes]
+       passlock tags delete <tag> [--yes]";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(1);
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let yes = has_flag(args, &["--yes", "-y"]);
+    let args: Vec<String> = args
+        .iter()
+        .filter(|a| !a.starts_with('-'))
+        .map(|a| a.trim().to_lowercase())
+        .collect();
+
+    let pwd = master_pwd()?;
+    let mut vault = storage::ld_vt(&pwd)?;
+    let counts = vault.tag_counts();
+    let count = |tag: &str| counts.iter().find(|(t, _)| t == tag).map_or(0, |(_, n)| *n);
+
+    let (from, to, verb) = match args.first().map(String::as_str) {
+        None => {
+            for (tag, n) in &counts {
+                println!("{tag}\t{n}");
+            }
+            return Ok(());
+        }
+        Some("rename") => {
+            let [_, old, new] = args.as_slice() else {
+                usage()
+            };
+            if count(new) > 0 {
+                return Err(format!("[X] Tag '{new}' already exists, use merge instead").into());
+            }
+            (
+                vec![old.clone()],
+                Some(new.clone()),
+                format!("Rename '{old}' to '{new}'"),
+            )
+        }
+        Some("merge") => {
+            let [_, from @ .., into] = args.as_slice() else {
+                usage()
+            };
+            if from.is_empty() {
+                usage();
+            }
+            let verb = format!("Merge {} into '{into}'", from.join(", "));
+            (from.to_vec(), Some(into.clone()), verb)
+        }
+        Some("delete") => {
+            let [_, tag] = args.as_slice() else { usage() };
+            (vec![tag.clone()], None, format!("Delete '{tag}'"))
+        }
+        Some(_) => usage(),
+    };
+
+    if let Some(missing) = from.iter().find(|t| count(t) == 0) {
+        return Err(format!("[X] No tag named '{missing}'").into());
+    }
+    let affected = if from.len() == 1 {
+        count(&from[0])
+    } else {
+        vault
+            .e
+            .iter()
+            .filter(|e| e.tags.iter().any(|t| from.contains(t)))
+            .count()
+    };
+    println!("{verb}: affects {affected} entries");
+    if !yes && !confirm("Proceed?")? {
+        println!("Aborted.");
+        return Ok(());
+    }
+    let changed = vault.retag(&from, to.as_deref());
+    storage::svv(&vault, &pwd)?;
+    println!("[✔] Updated {changed} entries");
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use super::{find_entry, master_pwd};
+use crate::models::FieldKind;
+use crate::storage;
+use crate::totp::OtpKind;
+use std::io::{self, IsTerminal};
+
+const USAGE: &str = "Usage: passlock totp <entry>";
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(query) = args.first().filter(|a| !a.starts_with("--")) else {
+        eprintln!("{USAGE}");
+        std::process::exit(1);
+    };
+
+    let pwd = master_pwd()?;
+    let mut vault = storage::ld_vt(&pwd)?;
+    let id = find_entry(&vault, query)?.id.clone();
+    let entry = vault
+        .e
+        .iter_mut()
+        .find(|e| e.id == id)
+        .ok_or("[X] Entry not found")?;
+    let mut params = entry
+        .otp_params()
+        .ok_or_else(|| format!("[X] '{}' has no TOTP secret", entry.n))?
+        .map_err(|e| format!("[X] Invalid TOTP on '{}': {e}", entry.n))?;
+
+    let (code, remaining) = params.now();
+    println!("{code}");
+    if let Some(secs) = remaining {
+        if io::stderr().is_terminal() {
+            eprintln!("[i] Valid for {secs}s");
+        }
+    }
+
+    // HOTP codes are single-use: advance the stored counter so the next
+    // call yields the next code. TOTP leaves the vault untouched.
+    if let OtpKind::Hotp { counter } = params.kind {
+        params.kind = OtpKind::Hotp {
+            counter: counter + 1,
+        };
+        let uri = params.to_uri();
+        if entry.otp.is_some() {
+            entry.otp = Some(uri);
+        } else if let Some(f) = entry.fields.iter_mut().find(|f| f.kind == FieldKind::Totp) {
+            f.value = uri;
+        }
+        entry.last_modified = crate::get_timestamp();
+        entry.uses += 1;
+        storage::svv(&vault, &pwd)?;
+    }
+    Ok(())
+}
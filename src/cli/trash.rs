@@ -0,0 +1,116 @@
+use super::{confirm, find_entry, has_flag, iso8601, master_pwd};
+use crate::attachments;
+use crate::models::Vault;
+use crate::storage;
+
+const USAGE: &str = "Usage: passlock trash [ls]
+       passlock trash rm <entry>
+       passlock trash restore <entry>
+       passlock trash purge <entry>|--all [--yes]
+       passlock trash days [<n>]";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(1);
+}
+
+/// Looks an entry up in the trash by id or name.
+fn trashed_id(v: &Vault, query: &str) -> Result<String, String> {
+    if let Some(e) = v.trash.iter().find(|e| e.id == query) {
+        return Ok(e.id.clone());
+    }
+    let lower = query.to_lowercase();
+    let matches: Vec<_> = v
+        .trash
+        .iter()
+        .filter(|e| e.n.to_lowercase() == lower || e.path().to_lowercase() == lower)
+        .collect();
+    match matches.as_slice() {
+        [] => Err(format!("[X] No trashed entry named '{query}'")),
+        [e] => Ok(e.id.clone()),
+        _ => Err(format!(
+            "[X] '{query}' matches several trashed entries, use an id: {}",
+            matches
+                .iter()
+                .map(|e| e.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let pwd = master_pwd()?;
+    let mut vault = storage::ld_vt(&pwd)?;
+    let now = crate::get_timestamp();
+    let mut purged = vault.purge_expired(now);
+    let mut dirty = purged > 0;
+
+    match args.first().map(String::as_str) {
+        None | Some("ls") => {
+            for e in &vault.trash {
+                println!(
+                    "{}\t{}\t{}",
+                    e.id,
+                    e.path(),
+                    iso8601(e.deleted.unwrap_or(e.last_modified))
+                );
+            }
+        }
+        Some("rm") => {
+            let Some(query) = args.get(1) else { usage() };
+            let id = find_entry(&vault, query)?.id.clone();
+            let name = vault.trash_entry(&id).unwrap_or_default();
+            dirty = true;
+            println!("[✔] Moved {name} to the trash");
+        }
+        Some("restore") => {
+            let Some(query) = args.get(1) else { usage() };
+            let id = trashed_id(&vault, query)?;
+            let name = vault.restore_entry(&id)?;
+            dirty = true;
+            println!("[✔] Restored {name}");
+        }
+        Some("purge") => {
+            let ids: Vec<String> = if has_flag(args, &["--all"]) {
+                vault.trash.iter().map(|e| e.id.clone()).collect()
+            } else {
+                let Some(query) = args.get(1) else { usage() };
+                vec![trashed_id(&vault, query)?]
+            };
+            if ids.is_empty() {
+                println!("[i] Trash is empty");
+            } else if has_flag(args, &["--yes", "-y"])
+                || confirm(&format!("Purge {} entries for good?", ids.len()))?
+            {
+                for id in &ids {
+                    vault.purge_entry(id, now);
+                }
+                purged += ids.len();
+                dirty = true;
+                println!("[✔] Purged {} entries", ids.len());
+            } else {
+                println!("[i] Aborted");
+                return Ok(());
+            }
+        }
+        Some("days") => match args.get(1) {
+            None => println!("{}", vault.trash_days),
+            Some(n) => {
+                vault.trash_days = n.parse().map_err(|_| format!("[X] Invalid days '{n}'"))?;
+                purged += vault.purge_expired(now);
+                dirty = true;
+                println!("[✔] Trash auto-purge set to {n} days (0 = never)");
+            }
+        },
+        Some(_) => usage(),
+    }
+
+    if dirty {
+        storage::svv(&vault, &pwd)?;
+    }
+    if purged > 0 {
+        attachments::gc(&vault)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! System clipboard through the `wl-copy`/`wl-paste` (Wayland) or `xclip`
+//! (X11) commands, so no display library is linked in.
+//!
+//! Only a SHA-256 digest of a copied secret is kept for the later "is it
+//! still ours?" check.
+
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Seconds before a copied secret is cleared when the config sets none.
+pub const DEFAULT_CLEAR_SECS: u64 = 20;
+
+pub type Tag = [u8; 32];
+
+pub fn tag(text: &str) -> Tag {
+    Sha256::digest(text.as_bytes()).into()
+}
+
+fn wayland() -> Result<bool, String> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        Ok(true)
+    } else if std::env::var_os("DISPLAY").is_some() {
+        Ok(false)
+    } else {
+        Err("No clipboard: neither WAYLAND_DISPLAY nor DISPLAY is set".to_string())
+    }
+}
+
+/// Puts `text` on the clipboard. Both tools fork a process that keeps
+/// serving the selection, so its output is detached from the terminal.
+pub fn copy(text: &str) -> Result<(), String> {
+    let (cmd, args): (&str, &[&str]) = if wayland()? {
+        ("wl-copy", &[])
+    } else {
+        ("xclip", &["-selection", "clipboard"])
+    };
+    let mut child = Command::new(cmd)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Cannot run {cmd}: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("{cmd}: {e}"))?;
+    }
+    let status = child.wait().map_err(|e| format!("{cmd}: {e}"))?;
+    if !status.success() {
+        return Err(format!("{cmd} failed ({status})"));
+    }
+    Ok(())
+}
+
+fn paste() -> Result<String, String> {
+    let (cmd, args): (&str, &[&str]) = if wayland()? {
+        ("wl-paste", &["--no-newline"])
+    } else {
+        ("xclip", &["-selection", "clipboard", "-o"])
+    };
+    let out = Command::new(cmd)
+        .args(args)
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| format!("Cannot run {cmd}: {e}"))?;
+    if !out.status.success() {
+        // Both tools fail on an empty clipboard.
+        return Ok(String::new());
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// Empties the clipboard if it still holds the value `tag` was made from,
+/// leaving anything copied since alone. Returns whether it cleared.
+pub fn clear_if(tag: &Tag) -> Result<bool, String> {
+    if self::tag(&paste()?) != *tag {
+        return Ok(false);
+    }
+    if wayland()? {
+        let status = Command::new("wl-copy")
+            .arg("--clear")
+            .status()
+            .map_err(|e| format!("Cannot run wl-copy: {e}"))?;
+        if !status.success() {
+            return Err(format!("wl-copy failed ({status})"));
+        }
+    } else {
+        copy("")?;
+    }
+    Ok(true)
+}
@@ -0,0 +1,75 @@
+use crate::models::SortMode;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// User preferences kept outside the vault, in `~/.passlock.config`.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Config {
+    #[serde(default)]
+    pub sort: SortMode,
+    /// Days after which the audit reports a password as old.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit_max_age: Option<u32>,
+    /// Seconds before a copied secret is cleared from the clipboard; 0
+    /// leaves it there.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clip_clear_secs: Option<u64>,
+    /// Seconds a revealed secret stays on screen.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reveal_secs: Option<u64>,
+    /// Presentation mode: every secret is masked and cannot be revealed.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub present: bool,
+    /// Minutes without a key press before the TUI locks; 0 never locks.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock_mins: Option<u64>,
+    /// Lock when the terminal reports that it lost focus (default on).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock_on_blur: Option<bool>,
+    /// One line per entry in the TUI lists.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub compact: bool,
+}
+
+pub const DEFAULT_REVEAL_SECS: u64 = 15;
+pub const DEFAULT_LOCK_MINS: u64 = 5;
+
+impl Config {
+    pub fn clip_secs(&self) -> u64 {
+        self.clip_clear_secs
+            .unwrap_or(crate::clipboard::DEFAULT_CLEAR_SECS)
+    }
+
+    pub fn reveal_secs(&self) -> u64 {
+        self.reveal_secs.unwrap_or(DEFAULT_REVEAL_SECS)
+    }
+
+    pub fn lock_mins(&self) -> u64 {
+        self.lock_mins.unwrap_or(DEFAULT_LOCK_MINS)
+    }
+
+    pub fn lock_on_blur(&self) -> bool {
+        self.lock_on_blur.unwrap_or(true)
+    }
+}
+
+/// Loads the config, falling back to defaults if it is missing or invalid.
+pub fn load() -> Config {
+    fs::read_to_string(storage::cfg_p())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(cfg: &Config) -> Result<(), String> {
+    let j = serde_json::to_string_pretty(cfg).map_err(|e| e.to_string())?;
+    fs::write(storage::cfg_p(), j).map_err(|e| e.to_string())
+}
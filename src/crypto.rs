@@ -1,5 +1,6 @@
+use crate::policy::{self, Mode, Policy, CONSONANTS, VOWELS};
+use crate::strength;
 use crate::vault_ffi;
-use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -8,6 +9,9 @@ pub struct PasswordStrength {
     pub strength: String,
     pub percentage: i32,
     pub feedback: Vec<String>,
+    pub guesses_log10: f64,
+    /// Offline attack against the vault's slow hash.
+    pub crack_time: String,
 }
 
 pub fn init_crypto() -> Result<(), String> {
@@ -18,14 +22,10 @@ pub fn cleanup() {
     vault_ffi::cleanup();
 }
 
-pub fn gen_salt() -> String {
-    if let Ok(salt_bytes) = vault_ffi::generate_salt() {
-        hex::encode(salt_bytes)
-    } else {
-        let mut rng = rand::thread_rng();
-        let salt_bytes: Vec<u8> = (0..vault_ffi::SALT_LENGTH).map(|_| rng.gen()).collect();
-        hex::encode(salt_bytes)
-    }
+/// A fresh random salt, hex encoded. Fails rather than fall back to a
+/// weaker generator.
+pub fn gen_salt() -> Result<String, String> {
+    vault_ffi::generate_salt().map(hex::encode)
 }
 
 pub fn enc(data: &[u8], pwd: &str, salt_hex: &str) -> Result<Vec<u8>, String> {
@@ -38,101 +38,118 @@ pub fn dec(data: &[u8], pwd: &str, salt_hex: &str) -> Result<Vec<u8>, String> {
     vault_ffi::decrypt_data(data, pwd, &salt)
 }
 
-pub fn gen_pwd(len: usize) -> String {
-    let chars =
-        "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%^&*()-_=+[]{}|;:,.<>?";
-    let mut rng = rand::thread_rng();
-    (0..len)
-        .map(|_| {
-            let idx = rng.gen_range(0..chars.len());
-            chars.chars().nth(idx).unwrap()
-        })
-        .collect()
+/// A uniform index below `n`. Every secret the app generates draws from
+/// this, backed by libsodium's `randombytes_uniform`.
+pub fn rand_below(n: usize) -> Result<usize, String> {
+    let n = u32::try_from(n).map_err(|_| "Range too large")?;
+    vault_ffi::random_uniform(n).map(|r| r as usize)
 }
 
-pub fn calc_pwd_strength(password: &str) -> PasswordStrength {
-    let mut score = 0;
-    let mut feedback = Vec::new();
-    let length = password.len();
-
-    if length >= 8 {
-        score += 1;
-    }
-    if length >= 12 {
-        score += 1;
-    }
-    if length >= 16 {
-        score += 1;
-    }
-    if length < 8 {
-        feedback.push("Use at least 8 characters".to_string());
-    }
-
-    let has_lower = password.chars().any(char::is_lowercase);
-    let has_upper = password.chars().any(char::is_uppercase);
-    let has_digit = password.chars().any(char::is_numeric);
-    let has_special = password
-        .chars()
-        .any(|c| "!@#$%^&*()_+-=[]{}|;:,.<>?".contains(c));
-
-    if has_lower {
-        score += 1;
-    } else {
-        feedback.push("Add lowercase letters".to_string());
+/// Words from the EFF list joined by the separator, optionally capitalized
+/// and with one digit or symbol inserted into a random word.
+fn gen_passphrase(policy: &Policy, n: usize) -> Result<String, String> {
+    let list = policy::eff_words();
+    let mut words = Vec::with_capacity(n);
+    for _ in 0..n {
+        let mut w = list[rand_below(list.len())?].to_string();
+        if policy.capitalize {
+            w[..1].make_ascii_uppercase();
+        }
+        words.push(w);
     }
-
-    if has_upper {
-        score += 1;
-    } else {
-        feedback.push("Add uppercase letters".to_string());
-    }
-
-    if has_digit {
-        score += 1;
-    } else {
-        feedback.push("Add numbers".to_string());
+    if policy.insert {
+        let chars = policy.insert_chars();
+        let c = chars[rand_below(chars.len())?];
+        let w = &mut words[rand_below(n)?];
+        let at = rand_below(w.len() + 1)?;
+        w.insert(at, c);
     }
+    Ok(words.join(&policy.separator))
+}
 
-    if has_special {
-        score += 1;
-    } else {
-        feedback.push("Add special characters".to_string());
+/// Generates a password meeting every minimum, filling the rest from
+/// all enabled classes and shuffling the result, or a passphrase when the
+/// policy asks for words.
+pub fn gen_pwd(policy: &Policy) -> Result<String, String> {
+    policy.validate()?;
+    match policy.mode() {
+        Mode::Words => return gen_passphrase(policy, policy.words.unwrap_or_default()),
+        Mode::Pattern => {
+            return policy
+                .pattern_sets()?
+                .iter()
+                .map(|set| Ok(set[rand_below(set.len())?]))
+                .collect();
+        }
+        Mode::Pronounceable => {
+            let (c, v): (Vec<char>, Vec<char>) =
+                (CONSONANTS.chars().collect(), VOWELS.chars().collect());
+            return (0..policy.len)
+                .map(|i| {
+                    let set = if i % 2 == 0 { &c } else { &v };
+                    Ok(set[rand_below(set.len())?])
+                })
+                .collect();
+        }
+        Mode::Chars => {}
     }
-
-    let lower_pwd = password.to_lowercase();
-    let common_patterns = ["123", "abc", "password", "qwerty", "admin"];
-    for pattern in &common_patterns {
-        if lower_pwd.contains(pattern) {
-            score -= 2;
-            feedback.push("Avoid common patterns".to_string());
-            break;
+    let mut classes = policy.classes();
+    classes.retain(|c| c.min.is_some());
+    let mut out: Vec<char> = Vec::with_capacity(policy.len);
+    let take = |pool: &mut Vec<char>| -> Result<char, String> {
+        let i = rand_below(pool.len())?;
+        Ok(if policy.no_repeat {
+            pool.swap_remove(i)
+        } else {
+            pool[i]
+        })
+    };
+    let mut rest: Vec<char> = Vec::new();
+    for c in &mut classes {
+        for _ in 0..c.min.unwrap_or(0) {
+            out.push(take(&mut c.chars)?);
         }
+        rest.extend(&c.chars);
     }
-
-    if length >= 20 {
-        score += 1;
+    while out.len() < policy.len {
+        out.push(take(&mut rest)?);
     }
-
-    if score < 0 {
-        score = 0;
+    for i in (1..out.len()).rev() {
+        out.swap(i, rand_below(i + 1)?);
     }
+    Ok(out.into_iter().collect())
+}
 
+/// Scores `password` on 0..=8 from the guesses the estimator in `strength`
+/// expects an attacker to need, on a log scale from 10 guesses (0) to 10^12
+/// (8).
+pub fn calc_pwd_strength(password: &str, user_inputs: &[&str]) -> PasswordStrength {
+    let est = strength::estimate(password, user_inputs);
+    let guesses_log10 = est.guesses_log10();
+    let score = (((guesses_log10 - 1.0) * 8.0 / 11.0) as i32).clamp(0, 8);
     let (strength, percentage) = match score {
         0..=2 => ("Weak", 25),
         3..=4 => ("Fair", 50),
         5..=6 => ("Good", 75),
         _ => ("Strong", 100),
     };
+    let mut feedback = Vec::new();
+    if score < 7 {
+        let (warning, suggestions) = est.feedback();
+        feedback.extend(warning);
+        feedback.extend(suggestions);
+    }
 
     PasswordStrength {
         score,
         strength: strength.to_string(),
         percentage,
         feedback,
+        guesses_log10,
+        crack_time: est.crack_time(),
     }
 }
 
-#[allow(dead_code)]
 pub fn secure_wipe(data: &mut [u8]) {
     vault_ffi::secure_zero(data);
 }
@@ -143,21 +160,21 @@ mod tests {
 
     #[test]
     fn test_pwd_sw() {
-        let result = calc_pwd_strength("abc");
+        let result = calc_pwd_strength("abc", &[]);
         assert_eq!(result.strength, "Weak");
         assert!(result.score <= 2);
     }
 
     #[test]
     fn test_pwd_ss() {
-        let result = calc_pwd_strength("MyP@ssw0rd!VeryStrong");
+        let result = calc_pwd_strength("MyP@ssw0rd!VeryStrong", &[]);
         assert_eq!(result.strength, "Strong");
         assert!(result.score >= 7);
     }
 
     #[test]
     fn test_pwd_cp() {
-        let result = calc_pwd_strength("password123");
+        let result = calc_pwd_strength("password123", &[]);
         assert!(result
             .feedback
             .iter()
@@ -166,22 +183,77 @@ mod tests {
 
     #[test]
     fn test_gpwdl() {
-        let pwd = gen_pwd(16);
+        let pwd = gen_pwd(&Policy::default()).unwrap();
         assert_eq!(pwd.len(), 16);
     }
 
     #[test]
     fn test_gpwdv() {
-        let pwd = gen_pwd(20);
+        let pwd = gen_pwd(&Policy {
+            len: 20,
+            ..Policy::default()
+        })
+        .unwrap();
         let has_lower = pwd.chars().any(char::is_lowercase);
         let has_upper = pwd.chars().any(char::is_uppercase);
         assert!(has_lower || has_upper);
     }
 
+    /// Pearson's chi-squared statistic of `counts` against a uniform
+    /// distribution.
+    fn chi2(counts: &[usize]) -> f64 {
+        let total: usize = counts.iter().sum();
+        let expected = total as f64 / counts.len() as f64;
+        counts
+            .iter()
+            .map(|&c| (c as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    #[test]
+    fn test_rand_uniform() {
+        init_crypto().expect("libsodium init failed");
+        // 7 buckets do not divide 2^32, so a plain modulo would skew them.
+        let mut counts = [0usize; 7];
+        for _ in 0..70_000 {
+            counts[rand_below(7).unwrap()] += 1;
+        }
+        // 6 degrees of freedom; 22.46 is the 0.1% critical value.
+        assert!(chi2(&counts) < 22.46, "{counts:?}");
+        assert!(rand_below(0).is_err());
+    }
+
+    #[test]
+    fn test_gen_distribution() {
+        init_crypto().expect("libsodium init failed");
+        let policy = Policy {
+            len: 64,
+            lower: None,
+            upper: None,
+            digits: Some(0),
+            symbols: None,
+            ..Policy::default()
+        };
+        let mut counts = [0usize; 10];
+        let mut first = [0usize; 10];
+        for _ in 0..1000 {
+            let pwd = gen_pwd(&policy).unwrap();
+            for (i, c) in pwd.bytes().enumerate() {
+                counts[usize::from(c - b'0')] += 1;
+                if i == 0 {
+                    first[usize::from(c - b'0')] += 1;
+                }
+            }
+        }
+        // 9 degrees of freedom; 27.88 is the 0.1% critical value.
+        assert!(chi2(&counts) < 27.88, "{counts:?}");
+        assert!(chi2(&first) < 27.88, "{first:?}");
+    }
+
     #[test]
     fn test_sgen() {
-        let salt1 = gen_salt();
-        let salt2 = gen_salt();
+        let salt1 = gen_salt().unwrap();
+        let salt2 = gen_salt().unwrap();
         assert_ne!(salt1, salt2);
         assert_eq!(salt1.len(), 32); // 16 bytes = 32 hex chars
     }
@@ -195,7 +267,7 @@ mod tests {
 
         let plaintext = b"Hello, World! This is a test.";
         let password = "test_password_123";
-        let salt = gen_salt();
+        let salt = gen_salt().unwrap();
 
         let encrypted = enc(plaintext, password, &salt).expect("Encryption failed");
         let decrypted = dec(&encrypted, password, &salt).expect("Decryption failed");
@@ -215,7 +287,7 @@ mod tests {
         let plaintext = b"Secret data";
         let password = "correct_password";
         let wrong_password = "wrong_password";
-        let salt = gen_salt();
+        let salt = gen_salt().unwrap();
 
         let encrypted = enc(plaintext, password, &salt).expect("Encryption failed");
         let result = dec(&encrypted, wrong_password, &salt);
@@ -1,24 +1,40 @@
+mod attachments;
+mod audit;
+mod breach;
+mod cli;
+mod clipboard;
+mod config;
 mod crypto;
 mod models;
+mod policy;
+mod search;
 mod storage;
+mod strength;
+mod totp;
 mod ui;
 mod vault_ffi;
 
 use models::Vault;
 use std::env;
 
-/// Generates a UUID string.
+/// Generates a random RFC 4122 version 4 UUID from the libsodium RNG.
 ///
 /// # Panics
-/// Panics if the system time is before `UNIX_EPOCH`.
+/// Panics if the RNG cannot be read.
 #[must_use]
 pub fn generate_uuid() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_nanos();
-    format!("{now}")
+    let mut b = vault_ffi::random_bytes(16).expect("libsodium RNG failed");
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let h = hex::encode(b);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &h[0..8],
+        &h[8..12],
+        &h[12..16],
+        &h[16..20],
+        &h[20..32]
+    )
 }
 
 /// Gets the current timestamp.
@@ -65,6 +81,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 let password = &args[2];
                 sync_vault(password)?;
             }
+            "export" => cli::export::run(&args[2..])?,
+            "emergency-kit" => cli::kit::run(&args[2..])?,
+            "get" => cli::get::run(&args[2..])?,
+            "__clip-clear" => cli::get::clip_clear(&args[2..])?,
+            "totp" => cli::totp::run(&args[2..])?,
+            "attach" => cli::attach::run(&args[2..])?,
+            "folders" => cli::folders::run(&args[2..])?,
+            "tags" => cli::tags::run(&args[2..])?,
+            "list" => cli::list::run(&args[2..])?,
+            "due" => cli::due::run(&args[2..])?,
+            "history" => cli::history::run(&args[2..])?,
+            "trash" => cli::trash::run(&args[2..])?,
+            "strength" => cli::strength::run(&args[2..])?,
+            "audit" => cli::audit::run(&args[2..])?,
+            "generate" => cli::generate::run(&args[2..])?,
+            "policy" => cli::generate::policy(&args[2..])?,
             _ => {
                 ui::run_tui()?;
             }
@@ -83,7 +115,7 @@ fn create_vault(password: &str) -> Result<(), Box<dyn std::error::Error>> {
         return Err("Vault already exists".into());
     }
 
-    let salt = crypto::gen_salt();
+    let salt = crypto::gen_salt()?;
     let vault = Vault::new(salt);
 
     storage::svv(&vault, password)?;
@@ -93,7 +125,8 @@ fn create_vault(password: &str) -> Result<(), Box<dyn std::error::Error>> {
 }
 
 fn unlock_vault(password: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let _vault = storage::ld_vt(password)?;
+    let vault = storage::ld_vt(password)?;
+    storage::wr_tmp(&vault)?;
     println!("[✔] Vault unlocked successfully.");
     Ok(())
 }
@@ -107,7 +140,8 @@ fn sync_vault(password: &str) -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let temp_data = std::fs::read_to_string(&temp_path)?;
-    let vault: Vault = serde_json::from_str(&temp_data)?;
+    let mut vault: Vault = serde_json::from_str(&temp_data)?;
+    vault.migrate_ids();
 
     storage::svv(&vault, password)?;
 
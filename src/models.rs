@@ -1,9 +1,316 @@
+use crate::policy::Policy;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Which entry column a history record belongs to.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistField {
+    #[default]
+    Password,
+    Username,
+    Url,
+}
+
+impl HistField {
+    pub fn label(self) -> &'static str {
+        match self {
+            HistField::Password => "Password",
+            HistField::Username => "Username",
+            HistField::Url => "URL",
+        }
+    }
+
+    /// Column key as used by `Entry::get_field`.
+    pub fn key(self) -> &'static str {
+        match self {
+            HistField::Password => "p",
+            HistField::Username => "u",
+            HistField::Url => "url",
+        }
+    }
+
+    fn is_password(&self) -> bool {
+        *self == HistField::Password
+    }
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct PasswordHistory {
+    /// The value `field` had before the change. Named for the time when only
+    /// passwords were tracked.
     pub password: String,
     pub changed_at: u64,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HistField::is_password")]
+    pub field: HistField,
+    /// Set when the change was a restore from history.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub restore: bool,
+}
+
+/// A file stored outside the vault JSON as its own encrypted blob.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Attachment {
+    pub name: String,
+    pub size: u64,
+    pub blob: String,
+    pub added: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    #[default]
+    Text,
+    Hidden,
+    Url,
+    Email,
+    Totp,
+}
+
+impl FieldKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FieldKind::Text => "text",
+            FieldKind::Hidden => "hidden",
+            FieldKind::Url => "url",
+            FieldKind::Email => "email",
+            FieldKind::Totp => "totp",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            FieldKind::Text => FieldKind::Hidden,
+            FieldKind::Hidden => FieldKind::Url,
+            FieldKind::Url => FieldKind::Email,
+            FieldKind::Email => FieldKind::Totp,
+            FieldKind::Totp => FieldKind::Text,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        match self {
+            FieldKind::Text => FieldKind::Totp,
+            FieldKind::Hidden => FieldKind::Text,
+            FieldKind::Url => FieldKind::Hidden,
+            FieldKind::Email => FieldKind::Url,
+            FieldKind::Totp => FieldKind::Email,
+        }
+    }
+
+    /// Secret kinds are masked in lists and never matched by search.
+    pub fn is_secret(&self) -> bool {
+        matches!(self, FieldKind::Hidden | FieldKind::Totp)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CustomField {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub kind: FieldKind,
+}
+
+/// One input of an entry type's form. `key` is either a built-in column
+/// (`u`, `p`, `url`, `nt`) or a key into `Entry::data`.
+#[derive(PartialEq)]
+pub struct SchemaField {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub required: bool,
+    pub secret: bool,
+    pub multiline: bool,
+}
+
+const fn sf(
+    key: &'static str,
+    label: &'static str,
+    required: bool,
+    secret: bool,
+    multiline: bool,
+) -> SchemaField {
+    SchemaField {
+        key,
+        label,
+        required,
+        secret,
+        multiline,
+    }
+}
+
+const LOGIN_SCHEMA: &[SchemaField] = &[
+    sf("u", "Username", true, false, false),
+    sf("p", "Password", true, true, false),
+    sf("url", "URL", false, false, false),
+    sf("otp", "TOTP (otpauth:// or base32)", false, true, false),
+    sf("nt", "Notes", false, false, true),
+];
+const NOTE_SCHEMA: &[SchemaField] = &[sf("nt", "Body", true, true, true)];
+const CARD_SCHEMA: &[SchemaField] = &[
+    sf("holder", "Cardholder", true, false, false),
+    sf("number", "Number", true, true, false),
+    sf("expiry", "Expiry (MM/YY)", true, false, false),
+    sf("cvv", "CVV", false, true, false),
+    sf("nt", "Notes", false, false, true),
+];
+const IDENTITY_SCHEMA: &[SchemaField] = &[
+    sf("full_name", "Full name", true, false, false),
+    sf("address", "Address", false, false, false),
+    sf("phone", "Phone", false, false, false),
+    sf("nt", "Notes", false, false, true),
+];
+const SSH_SCHEMA: &[SchemaField] = &[
+    sf("private_key", "Private key", true, true, true),
+    sf("public_key", "Public key", false, false, false),
+    sf("p", "Passphrase", false, true, false),
+    sf("nt", "Notes", false, false, true),
+];
+const WIFI_SCHEMA: &[SchemaField] = &[
+    sf("ssid", "SSID", true, false, false),
+    sf(
+        "security",
+        "Security (WPA2/WPA3/WEP/Open)",
+        true,
+        false,
+        false,
+    ),
+    sf("p", "Password", false, true, false),
+    sf("nt", "Notes", false, false, true),
+];
+
+/// Order of entry listings. Favorites are always pinned above the rest.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortMode {
+    #[default]
+    Name,
+    Modified,
+    Created,
+    #[serde(rename = "age")]
+    PwdAge,
+    #[serde(rename = "used")]
+    MostUsed,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 5] = [
+        SortMode::Name,
+        SortMode::Modified,
+        SortMode::Created,
+        SortMode::PwdAge,
+        SortMode::MostUsed,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Name => "name",
+            SortMode::Modified => "modified",
+            SortMode::Created => "created",
+            SortMode::PwdAge => "age",
+            SortMode::MostUsed => "used",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.label() == s)
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// Sorts favorites first, then by the mode: name A-Z, newest modified or
+    /// created first, oldest password first, or most used first.
+    pub fn apply(self, entries: &mut [Entry]) {
+        entries.sort_by(|a, b| {
+            b.favorite.cmp(&a.favorite).then_with(|| match self {
+                SortMode::Name => a.n.to_lowercase().cmp(&b.n.to_lowercase()),
+                SortMode::Modified => b.last_modified.cmp(&a.last_modified),
+                SortMode::Created => b.t.cmp(&a.t),
+                SortMode::PwdAge => a.pwd_changed().cmp(&b.pwd_changed()),
+                SortMode::MostUsed => b.uses.cmp(&a.uses),
+            })
+        });
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    #[default]
+    Login,
+    Note,
+    Card,
+    Identity,
+    SshKey,
+    Wifi,
+}
+
+impl EntryKind {
+    pub const ALL: [EntryKind; 6] = [
+        EntryKind::Login,
+        EntryKind::Note,
+        EntryKind::Card,
+        EntryKind::Identity,
+        EntryKind::SshKey,
+        EntryKind::Wifi,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntryKind::Login => "Login",
+            EntryKind::Note => "Secure note",
+            EntryKind::Card => "Card",
+            EntryKind::Identity => "Identity",
+            EntryKind::SshKey => "SSH key",
+            EntryKind::Wifi => "Wi-Fi",
+        }
+    }
+
+    pub fn schema(&self) -> &'static [SchemaField] {
+        match self {
+            EntryKind::Login => LOGIN_SCHEMA,
+            EntryKind::Note => NOTE_SCHEMA,
+            EntryKind::Card => CARD_SCHEMA,
+            EntryKind::Identity => IDENTITY_SCHEMA,
+            EntryKind::SshKey => SSH_SCHEMA,
+            EntryKind::Wifi => WIFI_SCHEMA,
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|k| k == self).unwrap_or(0)
+    }
+
+    pub fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()].clone()
+    }
+
+    pub fn prev(&self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()].clone()
+    }
+}
+
+fn luhn_ok(digits: &str) -> bool {
+    let mut sum = 0;
+    for (i, c) in digits.chars().rev().enumerate() {
+        let Some(mut d) = c.to_digit(10) else {
+            return false;
+        };
+        if i % 2 == 1 {
+            d *= 2;
+            if d > 9 {
+                d -= 9;
+            }
+        }
+        sum += d;
+    }
+    sum % 10 == 0
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -25,12 +332,321 @@ pub struct Entry {
     pub history: Vec<PasswordHistory>,
     #[serde(default)]
     pub last_modified: u64,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<CustomField>,
+    #[serde(default)]
+    pub kind: EntryKind,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub data: BTreeMap<String, String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otp: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub favorite: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub uses: u64,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotate_days: Option<u32>,
+    /// When the entry was moved to the trash.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted: Option<u64>,
+    /// Times the password showed up in a breach corpus at the last audit;
+    /// 0 when the corpus was a filter without counts.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breached: Option<u64>,
+    /// Generator rules the site requires.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<Policy>,
+    /// When the password was last changed, kept apart from the history so
+    /// it survives history being trimmed or turned off.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pwd_changed_at: Option<u64>,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+impl Entry {
+    /// Reads a schema value, whether it lives in a built-in column or `data`.
+    pub fn get_field(&self, key: &str) -> &str {
+        match key {
+            "u" => &self.u,
+            "p" => &self.p,
+            "url" => self.url.as_deref().unwrap_or_default(),
+            "nt" => self.nt.as_deref().unwrap_or_default(),
+            "otp" => self.otp.as_deref().unwrap_or_default(),
+            _ => self.data.get(key).map_or("", String::as_str),
+        }
+    }
+
+    pub fn set_field(&mut self, key: &str, value: &str) {
+        let opt = if value.is_empty() {
+            None
+        } else {
+            Some(value.to_string())
+        };
+        match key {
+            "u" => self.u = value.to_string(),
+            "p" => self.p = value.to_string(),
+            "url" => self.url = opt,
+            "nt" => self.nt = opt,
+            "otp" => self.otp = opt,
+            _ => {
+                if let Some(v) = opt {
+                    self.data.insert(key.to_string(), v);
+                } else {
+                    self.data.remove(key);
+                }
+            }
+        }
+    }
+
+    /// When the password was last set. Entries saved before the change time
+    /// was tracked fall back to the newest history record, then creation.
+    pub fn pwd_changed(&self) -> u64 {
+        self.pwd_changed_at.unwrap_or_else(|| {
+            self.history
+                .iter()
+                .rev()
+                .find(|h| h.field == HistField::Password)
+                .map_or(self.t, |h| h.changed_at)
+        })
+    }
+
+    /// Records that `field` used to be `old`, keeping at most `depth` records
+    /// per field. A depth of 0 turns history off.
+    pub fn push_history(&mut self, field: HistField, old: String, depth: usize, restore: bool) {
+        let now = crate::get_timestamp();
+        if field == HistField::Password {
+            // The audit result was about the old password.
+            self.breached = None;
+            self.pwd_changed_at = Some(now);
+        }
+        self.history.push(PasswordHistory {
+            password: old,
+            changed_at: now,
+            field,
+            restore,
+        });
+        self.trim_history(depth);
+    }
+
+    /// Drops the oldest records of each field beyond `depth`.
+    pub fn trim_history(&mut self, depth: usize) {
+        let mut keep = [depth; 3];
+        for i in (0..self.history.len()).rev() {
+            let left = &mut keep[self.history[i].field as usize];
+            if *left == 0 {
+                self.history.remove(i);
+            } else {
+                *left -= 1;
+            }
+        }
+    }
+
+    /// Swaps history record `idx` back in. The value it replaces goes into
+    /// history, marked as a restore.
+    pub fn restore_history(&mut self, idx: usize, depth: usize) -> Result<(), String> {
+        let rec = self
+            .history
+            .get(idx)
+            .ok_or("No such history record")?
+            .clone();
+        let key = rec.field.key();
+        let current = self.get_field(key).to_string();
+        if current == rec.password {
+            return Err(format!(
+                "{} is already set to that value",
+                rec.field.label()
+            ));
+        }
+        self.set_field(key, &rec.password);
+        self.push_history(rec.field, current, depth, true);
+        self.last_modified = crate::get_timestamp();
+        Ok(())
+    }
+
+    /// `folder/name`, or just the name for entries outside any folder.
+    pub fn path(&self) -> String {
+        match self.folder {
+            Some(ref folder) => format!("{folder}/{}", self.n),
+            None => self.n.clone(),
+        }
+    }
+
+    /// The entry's one-time password seed: the `otp` column, falling back to
+    /// the first TOTP custom field.
+    pub fn otp_params(&self) -> Option<Result<crate::totp::OtpParams, String>> {
+        self.otp
+            .as_deref()
+            .or_else(|| {
+                self.fields
+                    .iter()
+                    .find(|f| f.kind == FieldKind::Totp)
+                    .map(|f| f.value.as_str())
+            })
+            .filter(|s| !s.trim().is_empty())
+            .map(crate::totp::parse)
+    }
+
+    /// Checks required fields and the per-type formats of the entry's schema.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.n.trim().is_empty() {
+            return Err("Name is required!".to_string());
+        }
+        for f in self.kind.schema() {
+            if f.required && self.get_field(f.key).trim().is_empty() {
+                return Err(format!("{} is required!", f.label));
+            }
+        }
+        if let Some(Err(e)) = self.otp_params() {
+            return Err(format!("Invalid TOTP: {e}"));
+        }
+        match self.kind {
+            EntryKind::Card => {
+                let number: String = self
+                    .get_field("number")
+                    .chars()
+                    .filter(|c| !matches!(c, ' ' | '-'))
+                    .collect();
+                if !(12..=19).contains(&number.len()) || !luhn_ok(&number) {
+                    return Err("Card number is not valid".to_string());
+                }
+                let expiry = self.get_field("expiry");
+                let month = expiry
+                    .split_once('/')
+                    .filter(|(_, y)| y.len() == 2 || y.len() == 4)
+                    .and_then(|(m, y)| y.parse::<u32>().ok().and(m.parse::<u32>().ok()));
+                if !month.is_some_and(|m| (1..=12).contains(&m)) {
+                    return Err("Expiry must be MM/YY".to_string());
+                }
+                let cvv = self.get_field("cvv");
+                if !cvv.is_empty()
+                    && (!(3..=4).contains(&cvv.len()) || !cvv.chars().all(|c| c.is_ascii_digit()))
+                {
+                    return Err("CVV must be 3 or 4 digits".to_string());
+                }
+            }
+            EntryKind::Identity => {
+                let phone = self.get_field("phone");
+                if !phone
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || " +-().".contains(c))
+                {
+                    return Err("Phone may only contain digits, spaces and +-()".to_string());
+                }
+            }
+            EntryKind::SshKey => {
+                if !self
+                    .get_field("private_key")
+                    .trim_start()
+                    .starts_with("-----BEGIN")
+                {
+                    return Err("Private key must be in PEM/OpenSSH format".to_string());
+                }
+            }
+            EntryKind::Wifi => {
+                let security = self.get_field("security").to_uppercase();
+                if !["WPA2", "WPA3", "WPA", "WEP", "OPEN"].contains(&security.as_str()) {
+                    return Err("Security must be WPA2, WPA3, WPA, WEP or Open".to_string());
+                }
+                if security != "OPEN" && self.p.is_empty() {
+                    return Err("Password is required for secured networks".to_string());
+                }
+            }
+            EntryKind::Login | EntryKind::Note => {}
+        }
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Vault {
     pub e: Vec<Entry>,
     pub s: String,
+    #[serde(default)]
+    pub created: u64,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub blob_key: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tag_rotation: BTreeMap<String, u32>,
+    /// Old values kept per field and entry.
+    #[serde(default = "default_history_depth")]
+    pub history_depth: usize,
+    /// Deleted entries, kept until purged.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trash: Vec<Entry>,
+    /// Days an entry stays in the trash before it is purged; 0 keeps it
+    /// until purged by hand.
+    #[serde(default = "default_trash_days")]
+    pub trash_days: u32,
+    /// Ids of purged entries and when they were purged, so a merge can tell
+    /// "deleted here" from "never existed".
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tombstones: BTreeMap<String, u64>,
+}
+
+pub const DEFAULT_HISTORY_DEPTH: usize = 5;
+pub const DEFAULT_TRASH_DAYS: u32 = 30;
+
+fn default_history_depth() -> usize {
+    DEFAULT_HISTORY_DEPTH
+}
+
+fn default_trash_days() -> u32 {
+    DEFAULT_TRASH_DAYS
+}
+
+/// True for an RFC 4122 version 4 or 7 UUID in its canonical hyphenated
+/// form, the only ids current builds generate.
+pub fn is_uuid(id: &str) -> bool {
+    let b = id.as_bytes();
+    b.len() == 36
+        && b.iter().enumerate().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => *c == b'-',
+            _ => c.is_ascii_hexdigit(),
+        })
+        && matches!(b[14], b'4' | b'7')
+        && matches!(b[19].to_ascii_lowercase(), b'8' | b'9' | b'a' | b'b')
+}
+
+/// How far ahead `Vault::due` warns about upcoming rotations.
+pub const DUE_SOON_DAYS: i64 = 14;
+
+/// Normalises a folder path typed by the user: `/work//aws/ ` becomes
+/// `work/aws`, and an empty path means "no folder".
+pub fn norm_folder(path: &str) -> Option<String> {
+    let parts: Vec<&str> = path
+        .split('/')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("/"))
+    }
 }
 
 impl Vault {
@@ -38,6 +654,484 @@ impl Vault {
         Self {
             e: Vec::new(),
             s: salt,
+            created: crate::get_timestamp(),
+            blob_key: String::new(),
+            tag_rotation: BTreeMap::new(),
+            history_depth: DEFAULT_HISTORY_DEPTH,
+            trash: Vec::new(),
+            trash_days: DEFAULT_TRASH_DAYS,
+            tombstones: BTreeMap::new(),
+        }
+    }
+
+    /// Gives every entry (trashed ones included) whose id is a legacy
+    /// timestamp id, or repeats an earlier entry's id, a fresh UUID.
+    /// Returns how many ids changed.
+    pub fn migrate_ids(&mut self) -> usize {
+        let mut seen = HashSet::new();
+        let mut changed = 0;
+        for e in self.e.iter_mut().chain(self.trash.iter_mut()) {
+            if !is_uuid(&e.id) || !seen.insert(e.id.clone()) {
+                e.id = crate::generate_uuid();
+                seen.insert(e.id.clone());
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Moves entry `id` to the trash and returns its name.
+    pub fn trash_entry(&mut self, id: &str) -> Option<String> {
+        let idx = self.e.iter().position(|e| e.id == id)?;
+        let mut entry = self.e.remove(idx);
+        entry.deleted = Some(crate::get_timestamp());
+        let name = entry.n.clone();
+        self.trash.push(entry);
+        Some(name)
+    }
+
+    /// Moves entry `id` out of the trash, back where it was.
+    pub fn restore_entry(&mut self, id: &str) -> Result<String, String> {
+        let idx = self
+            .trash
+            .iter()
+            .position(|e| e.id == id)
+            .ok_or("Entry is not in the trash")?;
+        if self.e.iter().any(|e| e.id == id) {
+            return Err("An entry with the same id already exists".to_string());
+        }
+        let mut entry = self.trash.remove(idx);
+        entry.deleted = None;
+        let name = entry.n.clone();
+        self.e.push(entry);
+        Ok(name)
+    }
+
+    /// Deletes entry `id` from the trash for good, leaving a tombstone.
+    pub fn purge_entry(&mut self, id: &str, now: u64) -> Option<String> {
+        let idx = self.trash.iter().position(|e| e.id == id)?;
+        let entry = self.trash.remove(idx);
+        self.tombstones.insert(entry.id, now);
+        Some(entry.n)
+    }
+
+    /// Purges trash older than `trash_days`. Returns how many went.
+    pub fn purge_expired(&mut self, now: u64) -> usize {
+        if self.trash_days == 0 {
+            return 0;
+        }
+        let max_age = u64::from(self.trash_days) * 86400;
+        let expired: Vec<String> = self
+            .trash
+            .iter()
+            .filter(|e| now.saturating_sub(e.deleted.unwrap_or(now)) >= max_age)
+            .map(|e| e.id.clone())
+            .collect();
+        for id in &expired {
+            self.purge_entry(id, now);
+        }
+        expired.len()
+    }
+
+    /// Rotation interval in days: the entry's own, else the strictest of
+    /// its tags' intervals.
+    pub fn rotation_days(&self, e: &Entry) -> Option<u32> {
+        e.rotate_days.or_else(|| {
+            e.tags
+                .iter()
+                .filter_map(|t| self.tag_rotation.get(t).copied())
+                .min()
+        })
+    }
+
+    /// Whole days left before `e` must be rotated, negative once overdue.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn days_left(&self, e: &Entry, now: u64) -> Option<i64> {
+        let days = self.rotation_days(e)?;
+        let due_at = e.pwd_changed() + u64::from(days) * 86400;
+        Some((due_at as i64 - now as i64).div_euclid(86400))
+    }
+
+    /// Entries overdue or due within `DUE_SOON_DAYS`, most urgent first.
+    pub fn due(&self, now: u64) -> Vec<(&Entry, i64)> {
+        let mut out: Vec<(&Entry, i64)> = self
+            .e
+            .iter()
+            .filter_map(|e| self.days_left(e, now).map(|d| (e, d)))
+            .filter(|(_, d)| *d < DUE_SOON_DAYS)
+            .collect();
+        out.sort_by_key(|(_, d)| *d);
+        out
+    }
+
+    /// Tags with the number of entries carrying each, most used first.
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut tag_map: HashMap<String, usize> = HashMap::new();
+        for entry in &self.e {
+            for tag in &entry.tags {
+                *tag_map.entry(tag.clone()).or_insert(0) += 1;
+            }
+        }
+        let mut counts: Vec<(String, usize)> = tag_map.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
+    /// Replaces every tag in `from` with `to` (or just drops them when `to`
+    /// is `None`) across all entries. Rotation intervals follow the tags,
+    /// the shortest one winning a merge. Returns how many entries changed.
+    pub fn retag(&mut self, from: &[String], to: Option<&str>) -> usize {
+        let now = crate::get_timestamp();
+        let rotate = from
+            .iter()
+            .filter(|t| Some(t.as_str()) != to)
+            .filter_map(|t| self.tag_rotation.remove(t))
+            .min();
+        if let (Some(to), Some(days)) = (to, rotate) {
+            let cur = self.tag_rotation.entry(to.to_string()).or_insert(days);
+            *cur = (*cur).min(days);
+        }
+        let mut changed = 0;
+        for e in &mut self.e {
+            if !e.tags.iter().any(|t| from.contains(t)) {
+                continue;
+            }
+            e.tags.retain(|t| !from.contains(t));
+            if let Some(to) = to {
+                if !e.tags.iter().any(|t| t == to) {
+                    e.tags.push(to.to_string());
+                }
+            }
+            e.last_modified = now;
+            changed += 1;
+        }
+        changed
+    }
+
+    /// Every folder in use, including intermediate ones, in tree order.
+    pub fn folders(&self) -> BTreeSet<String> {
+        let mut out = BTreeSet::new();
+        for folder in self.e.iter().filter_map(|e| e.folder.as_deref()) {
+            let mut end = 0;
+            for part in folder.split('/') {
+                end += part.len();
+                out.insert(folder[..end].to_string());
+                end += 1;
+            }
+        }
+        out
+    }
+
+    /// Re-homes folder `from` and everything below it under `to`. Returns
+    /// how many entries moved.
+    pub fn move_folder(&mut self, from: &str, to: &str) -> Result<usize, String> {
+        let from = norm_folder(from).ok_or("Source folder is required")?;
+        let to = norm_folder(to);
+        if let Some(ref to) = to {
+            if to == &from || to.starts_with(&format!("{from}/")) {
+                return Err("Cannot move a folder into itself".to_string());
+            }
+        }
+        let now = crate::get_timestamp();
+        let mut moved = 0;
+        for e in &mut self.e {
+            let Some(ref folder) = e.folder else { continue };
+            let rest = if folder == &from {
+                ""
+            } else if let Some(rest) = folder.strip_prefix(&format!("{from}/")) {
+                rest
+            } else {
+                continue;
+            };
+            e.folder = match (&to, rest) {
+                (Some(to), "") => Some(to.clone()),
+                (Some(to), rest) => Some(format!("{to}/{rest}")),
+                (None, "") => None,
+                (None, rest) => Some(rest.to_string()),
+            };
+            e.last_modified = now;
+            moved += 1;
+        }
+        if moved == 0 {
+            return Err(format!("No folder named '{from}'"));
+        }
+        Ok(moved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: EntryKind) -> Entry {
+        Entry {
+            id: "1".to_string(),
+            n: "test".to_string(),
+            u: String::new(),
+            p: String::new(),
+            url: None,
+            nt: None,
+            t: 0,
+            tags: Vec::new(),
+            history: Vec::new(),
+            last_modified: 0,
+            fields: Vec::new(),
+            kind,
+            data: BTreeMap::new(),
+            otp: None,
+            attachments: Vec::new(),
+            folder: None,
+            favorite: false,
+            uses: 0,
+            rotate_days: None,
+            deleted: None,
+            breached: None,
+            policy: None,
+            pwd_changed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_legacy_login() {
+        let e: Entry = serde_json::from_str(r#"{"id":"1","n":"a","u":"b","p":"c","t":0}"#).unwrap();
+        assert_eq!(e.kind, EntryKind::Login);
+        assert!(e.validate().is_ok());
+    }
+
+    #[test]
+    fn test_custom_fields() {
+        let mut e = entry(EntryKind::Login);
+        e.fields = vec![
+            CustomField {
+                name: "pin".to_string(),
+                value: "1234".to_string(),
+                kind: FieldKind::Hidden,
+            },
+            CustomField {
+                name: "recovery".to_string(),
+                value: "a@b.example".to_string(),
+                kind: FieldKind::Email,
+            },
+        ];
+        let json = serde_json::to_value(&e).unwrap();
+        assert_eq!(json["fields"][0]["kind"], "hidden");
+        let back: Entry = serde_json::from_value(json).unwrap();
+        let fields: Vec<_> = back
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.value.as_str(), f.kind.clone()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("pin", "1234", FieldKind::Hidden),
+                ("recovery", "a@b.example", FieldKind::Email)
+            ]
+        );
+        // Fields written before kinds existed read back as text.
+        let f: CustomField = serde_json::from_str(r#"{"name":"a","value":"b"}"#).unwrap();
+        assert_eq!(f.kind, FieldKind::Text);
+    }
+
+    #[test]
+    fn test_card_val() {
+        let mut e = entry(EntryKind::Card);
+        e.set_field("holder", "A B");
+        e.set_field("number", "4111 1111 1111 1111");
+        e.set_field("expiry", "12/29");
+        assert!(e.validate().is_ok());
+        e.set_field("number", "4111 1111 1111 1112");
+        assert!(e.validate().is_err());
+        e.set_field("number", "4111111111111111");
+        e.set_field("expiry", "13/29");
+        assert!(e.validate().is_err());
+    }
+
+    #[test]
+    fn test_note_body() {
+        let mut e = entry(EntryKind::Note);
+        assert!(e.validate().is_err());
+        e.set_field("nt", "secret");
+        assert!(e.validate().is_ok());
+    }
+
+    #[test]
+    fn test_move_folder() {
+        let mut v = Vault::new(String::new());
+        for (id, folder) in [
+            ("1", "work/aws"),
+            ("2", "work/aws/prod"),
+            ("3", "work/awsx"),
+        ] {
+            let mut e = entry(EntryKind::Note);
+            e.id = id.to_string();
+            e.folder = norm_folder(folder);
+            v.e.push(e);
+        }
+        assert_eq!(v.move_folder("/work/aws/", "team/cloud").unwrap(), 2);
+        let folders: Vec<_> = v.e.iter().map(|e| e.folder.clone().unwrap()).collect();
+        assert_eq!(folders, ["team/cloud", "team/cloud/prod", "work/awsx"]);
+        assert!(v.move_folder("team", "team/cloud/x").is_err());
+        assert!(v.folders().contains("team"));
+    }
+
+    #[test]
+    fn test_retag() {
+        let mut v = Vault::new(String::new());
+        for tags in [vec!["a", "b"], vec!["b"], vec!["c"]] {
+            let mut e = entry(EntryKind::Note);
+            e.tags = tags.into_iter().map(String::from).collect();
+            v.e.push(e);
+        }
+        v.tag_rotation.insert("a".to_string(), 90);
+        v.tag_rotation.insert("b".to_string(), 30);
+        v.tag_rotation.insert("c".to_string(), 60);
+        let from = ["a".to_string(), "b".to_string()];
+        assert_eq!(v.retag(&from, Some("c")), 2);
+        assert_eq!(v.tag_counts(), [("c".to_string(), 3)]);
+        assert_eq!(
+            v.tag_rotation.iter().collect::<Vec<_>>(),
+            [(&"c".to_string(), &30)]
+        );
+        assert_eq!(v.retag(&["c".to_string()], Some("d")), 3);
+        assert_eq!(v.tag_rotation.get("d"), Some(&30));
+        assert_eq!(v.retag(&["d".to_string()], None), 3);
+        assert!(v.tag_counts().is_empty());
+        assert!(v.tag_rotation.is_empty());
+    }
+
+    #[test]
+    fn test_sort_favs() {
+        let mut entries = Vec::new();
+        for (name, fav, uses) in [("b", false, 5), ("a", false, 1), ("c", true, 0)] {
+            let mut e = entry(EntryKind::Note);
+            e.n = name.to_string();
+            e.favorite = fav;
+            e.uses = uses;
+            entries.push(e);
         }
+        SortMode::Name.apply(&mut entries);
+        let names: Vec<&str> = entries.iter().map(|e| e.n.as_str()).collect();
+        assert_eq!(names, ["c", "a", "b"]);
+        SortMode::MostUsed.apply(&mut entries);
+        let names: Vec<&str> = entries.iter().map(|e| e.n.as_str()).collect();
+        assert_eq!(names, ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn test_rotation_due() {
+        let day = 86400;
+        let mut v = Vault::new(String::new());
+        v.tag_rotation.insert("prod".to_string(), 90);
+        v.tag_rotation.insert("pci".to_string(), 30);
+        let mut a = entry(EntryKind::Login);
+        a.tags = vec!["prod".to_string(), "pci".to_string()];
+        let mut b = entry(EntryKind::Login);
+        b.tags = vec!["prod".to_string()];
+        b.rotate_days = Some(365);
+        let c = entry(EntryKind::Login);
+        v.e = vec![a, b, c];
+
+        assert_eq!(v.rotation_days(&v.e[0]), Some(30));
+        assert_eq!(v.rotation_days(&v.e[1]), Some(365));
+        assert_eq!(v.rotation_days(&v.e[2]), None);
+        assert_eq!(v.days_left(&v.e[0], 31 * day), Some(-1));
+        assert_eq!(v.due(20 * day).len(), 1);
+        assert!(v.due(day).is_empty());
+    }
+
+    #[test]
+    fn test_history_depth() {
+        let mut e = entry(EntryKind::Login);
+        e.p = "p0".to_string();
+        e.u = "u0".to_string();
+        e.push_history(HistField::Username, "old-user".to_string(), 2, false);
+        for i in 1..=4 {
+            let old = std::mem::replace(&mut e.p, format!("p{i}"));
+            e.push_history(HistField::Password, old, 2, false);
+        }
+        let pwds: Vec<&str> = e
+            .history
+            .iter()
+            .filter(|h| h.field == HistField::Password)
+            .map(|h| h.password.as_str())
+            .collect();
+        assert_eq!(pwds, ["p2", "p3"]);
+        assert_eq!(e.history.len(), 3);
+
+        let idx = e.history.iter().position(|h| h.password == "p2").unwrap();
+        e.restore_history(idx, 2).unwrap();
+        assert_eq!(e.p, "p2");
+        let last = e.history.last().unwrap();
+        assert!(last.restore && last.password == "p4");
+        let idx = e
+            .history
+            .iter()
+            .position(|h| h.password == "old-user")
+            .unwrap();
+        e.restore_history(idx, 2).unwrap();
+        assert_eq!(e.u, "old-user");
+    }
+
+    #[test]
+    fn test_history_off() {
+        let mut e = entry(EntryKind::Login);
+        e.t = 100;
+        assert_eq!(e.pwd_changed(), 100);
+        e.push_history(HistField::Password, "old".to_string(), 0, false);
+        assert!(e.history.is_empty());
+        assert!(e.pwd_changed() > 100);
+        let changed = e.pwd_changed();
+        e.push_history(HistField::Username, "old-user".to_string(), 0, false);
+        assert_eq!(e.pwd_changed(), changed);
+    }
+
+    #[test]
+    fn test_trash() {
+        let day = 86400;
+        let mut v = Vault::new(String::new());
+        let mut a = entry(EntryKind::Login);
+        a.id = "a".to_string();
+        let mut b = entry(EntryKind::Login);
+        b.id = "b".to_string();
+        v.e = vec![a, b];
+
+        assert!(v.trash_entry("a").is_some());
+        assert!(v.trash_entry("a").is_none());
+        assert_eq!(v.e.len(), 1);
+        v.restore_entry("a").unwrap();
+        assert!(v.e.iter().all(|e| e.deleted.is_none()));
+
+        v.trash_entry("a");
+        v.trash_entry("b");
+        v.trash[0].deleted = Some(0);
+        let now = 31 * day;
+        v.trash[1].deleted = Some(now - day);
+        assert_eq!(v.purge_expired(now), 1);
+        assert_eq!(v.tombstones.get("a"), Some(&now));
+        assert_eq!(v.trash.len(), 1);
+        v.trash_days = 0;
+        assert_eq!(v.purge_expired(now * 10), 0);
+        assert!(v.restore_entry("a").is_err());
+    }
+
+    #[test]
+    fn test_migrate_ids() {
+        let id = crate::generate_uuid();
+        assert!(is_uuid(&id));
+        assert_ne!(id, crate::generate_uuid());
+        assert!(!is_uuid("1717171717171717171"));
+
+        let mut v = Vault::new(String::new());
+        let mut a = entry(EntryKind::Login);
+        a.id = id.clone();
+        let b = a.clone();
+        let c = entry(EntryKind::Login);
+        v.e = vec![a, b, c];
+        assert_eq!(v.migrate_ids(), 2);
+        assert_eq!(v.e[0].id, id);
+        assert!(v.e.iter().all(|e| is_uuid(&e.id)));
+        assert_ne!(v.e[1].id, v.e[2].id);
+        assert_eq!(v.migrate_ids(), 0);
     }
 }
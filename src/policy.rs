@@ -0,0 +1,605 @@
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+pub const DEFAULT_SYMBOLS: &str = "!@#$%^&*()-_=+[]{}|;:,.<>?";
+/// Characters easily confused with one another in print.
+pub const AMBIGUOUS: &str = "0Oo1lI|";
+pub const MIN_LEN: usize = 4;
+pub const MAX_LEN: usize = 64;
+pub const MIN_WORDS: usize = 3;
+pub const MAX_WORDS: usize = 20;
+pub const DEFAULT_WORDS: usize = 6;
+pub const DEFAULT_SEPARATOR: &str = "-";
+pub const DEFAULT_PATTERN: &str = "Cvccvc-99-Cvccvc";
+/// Longest password a pattern may expand to.
+pub const MAX_PATTERN: usize = 128;
+pub const CONSONANTS: &str = "bcdfghjklmnprstvwz";
+pub const VOWELS: &str = "aeiou";
+
+/// What a policy generates. Not stored as such: a pattern takes precedence
+/// over words, and words over the pronounceable flag.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    Chars,
+    Words,
+    Pronounceable,
+    Pattern,
+}
+
+impl Mode {
+    pub fn label(self) -> &'static str {
+        match self {
+            Mode::Chars => "Characters",
+            Mode::Words => "Passphrase (EFF wordlist)",
+            Mode::Pronounceable => "Pronounceable",
+            Mode::Pattern => "Pattern",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Mode::Chars => Mode::Words,
+            Mode::Words => Mode::Pronounceable,
+            Mode::Pronounceable => Mode::Pattern,
+            Mode::Pattern => Mode::Chars,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        self.next().next().next()
+    }
+}
+
+/// The EFF large wordlist: 7776 words, one per roll of five dice.
+pub fn eff_words() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| {
+        include_str!("data/eff_large_wordlist.txt")
+            .lines()
+            .filter_map(|l| l.split_once('\t').map(|(_, w)| w))
+            .collect()
+    })
+}
+
+/// Rules for generated passwords. Saved on an entry so that regenerating
+/// follows the site's requirements. A class set to `None` is left out;
+/// `Some(n)` requires at least `n` characters of it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Policy {
+    pub len: usize,
+    pub lower: Option<usize>,
+    pub upper: Option<usize>,
+    pub digits: Option<usize>,
+    pub symbols: Option<usize>,
+    /// Symbols to draw from; empty means `DEFAULT_SYMBOLS`.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub symbol_set: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub no_ambiguous: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub no_repeat: bool,
+    /// A passphrase of this many words instead of random characters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words: Option<usize>,
+    #[serde(skip_serializing_if = "is_default_separator")]
+    pub separator: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub capitalize: bool,
+    /// Insert one random digit or symbol into a random word.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub insert: bool,
+    /// Alternating consonants and vowels, `len` letters long.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub pronounceable: bool,
+    /// A template such as `Cvccvc-99` or `[A-F0-9]{32}`; see `parse_pattern`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+fn is_default_separator(s: &str) -> bool {
+    s == DEFAULT_SEPARATOR
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy {
+            len: 16,
+            lower: Some(1),
+            upper: Some(1),
+            digits: Some(1),
+            symbols: Some(1),
+            symbol_set: String::new(),
+            no_ambiguous: false,
+            no_repeat: false,
+            words: None,
+            separator: DEFAULT_SEPARATOR.to_string(),
+            capitalize: false,
+            insert: false,
+            pronounceable: false,
+            pattern: None,
+        }
+    }
+}
+
+/// A character class: its name, minimum and allowed characters.
+pub struct Class {
+    pub name: &'static str,
+    pub min: Option<usize>,
+    pub chars: Vec<char>,
+}
+
+/// Expands a pattern into the characters allowed at each position:
+///
+/// * `c`/`C` consonant, `v`/`V` vowel, `a`/`A` letter (lower/upper case)
+/// * `d` or `9` digit, `s` symbol, `x` letter or digit
+/// * `[...]` any character listed, with ranges such as `A-F`
+/// * `{n}` repeats the previous item `n` times
+/// * `\` escapes the next character; anything else is literal
+pub fn parse_pattern(pattern: &str, symbols: &[char]) -> Result<Vec<Vec<char>>, String> {
+    let lower = |s: &str| s.chars().collect::<Vec<char>>();
+    let upper = |s: &str| s.to_uppercase().chars().collect::<Vec<char>>();
+    let letters = "abcdefghijklmnopqrstuvwxyz";
+    let mut out: Vec<Vec<char>> = Vec::new();
+    let mut it = pattern.chars().peekable();
+    while let Some(c) = it.next() {
+        let set = match c {
+            'c' => lower(CONSONANTS),
+            'C' => upper(CONSONANTS),
+            'v' => lower(VOWELS),
+            'V' => upper(VOWELS),
+            'a' => lower(letters),
+            'A' => upper(letters),
+            'd' | '9' => ('0'..='9').collect(),
+            's' => symbols.to_vec(),
+            'x' => {
+                let mut set = lower(letters);
+                set.extend(upper(letters));
+                set.extend('0'..='9');
+                set
+            }
+            '\\' => vec![it.next().ok_or("Pattern ends with '\\'")?],
+            '[' => {
+                let mut set = Vec::new();
+                loop {
+                    let c = match it.next() {
+                        None => return Err("Unclosed '[' in pattern".to_string()),
+                        Some(']') => break,
+                        Some('\\') => it.next().ok_or("Unclosed '[' in pattern")?,
+                        Some(c) => c,
+                    };
+                    if it.peek() == Some(&'-') {
+                        it.next();
+                        match it.next() {
+                            Some(']') => {
+                                set.extend([c, '-']);
+                                break;
+                            }
+                            Some(end) if end >= c => set.extend(c..=end),
+                            _ => return Err(format!("Bad range starting at '{c}'")),
+                        }
+                    } else {
+                        set.push(c);
+                    }
+                }
+                set.sort_unstable();
+                set.dedup();
+                if set.is_empty() {
+                    return Err("Empty '[]' in pattern".to_string());
+                }
+                set
+            }
+            '{' => {
+                let mut digits = String::new();
+                loop {
+                    match it.next() {
+                        Some('}') => break,
+                        Some(d) if d.is_ascii_digit() => digits.push(d),
+                        _ => return Err("Expected '{n}' in pattern".to_string()),
+                    }
+                }
+                let n: usize = digits.parse().map_err(|_| "Expected '{n}' in pattern")?;
+                let last = out.pop().ok_or("'{n}' needs something to repeat")?;
+                if n > MAX_PATTERN {
+                    return Err(format!("Patterns are limited to {MAX_PATTERN} characters"));
+                }
+                out.extend(std::iter::repeat_n(last, n));
+                continue;
+            }
+            c => vec![c],
+        };
+        if set.is_empty() {
+            return Err(format!("'{c}' has no characters to choose from"));
+        }
+        out.push(set);
+    }
+    if out.is_empty() {
+        return Err("Pattern is empty".to_string());
+    }
+    if out.len() > MAX_PATTERN {
+        return Err(format!("Patterns are limited to {MAX_PATTERN} characters"));
+    }
+    Ok(out)
+}
+
+impl Policy {
+    pub fn mode(&self) -> Mode {
+        if self.pattern.is_some() {
+            Mode::Pattern
+        } else if self.words.is_some() {
+            Mode::Words
+        } else if self.pronounceable {
+            Mode::Pronounceable
+        } else {
+            Mode::Chars
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.words = (mode == Mode::Words).then_some(self.words.unwrap_or(DEFAULT_WORDS));
+        self.pronounceable = mode == Mode::Pronounceable;
+        self.pattern = (mode == Mode::Pattern).then(|| {
+            self.pattern
+                .take()
+                .unwrap_or_else(|| DEFAULT_PATTERN.to_string())
+        });
+    }
+
+    /// The per-position character sets of the pattern.
+    pub fn pattern_sets(&self) -> Result<Vec<Vec<char>>, String> {
+        let symbols = self.classes().swap_remove(3).chars;
+        parse_pattern(self.pattern.as_deref().unwrap_or_default(), &symbols)
+    }
+
+    /// The enabled and disabled classes with ambiguous characters removed.
+    pub fn classes(&self) -> Vec<Class> {
+        let set = if self.symbol_set.is_empty() {
+            DEFAULT_SYMBOLS
+        } else {
+            &self.symbol_set
+        };
+        let mut symbols: Vec<char> = Vec::new();
+        for c in set.chars() {
+            if !c.is_whitespace() && !c.is_alphanumeric() && !symbols.contains(&c) {
+                symbols.push(c);
+            }
+        }
+        [
+            ("lowercase", self.lower, ('a'..='z').collect()),
+            ("uppercase", self.upper, ('A'..='Z').collect()),
+            ("digits", self.digits, ('0'..='9').collect()),
+            ("symbols", self.symbols, symbols),
+        ]
+        .into_iter()
+        .map(|(name, min, chars): (_, _, Vec<char>)| Class {
+            name,
+            min,
+            chars: chars
+                .into_iter()
+                .filter(|c| !self.no_ambiguous || !AMBIGUOUS.contains(*c))
+                .collect(),
+        })
+        .collect()
+    }
+
+    /// Checks that a password satisfying the policy can exist.
+    pub fn validate(&self) -> Result<(), String> {
+        match self.mode() {
+            Mode::Pattern => return self.pattern_sets().map(|_| ()),
+            Mode::Words => {
+                let n = self.words.unwrap_or_default();
+                if !(MIN_WORDS..=MAX_WORDS).contains(&n) {
+                    return Err(format!("Word count must be {MIN_WORDS}-{MAX_WORDS}"));
+                }
+                return Ok(());
+            }
+            Mode::Pronounceable | Mode::Chars => {}
+        }
+        if !(MIN_LEN..=MAX_LEN).contains(&self.len) {
+            return Err(format!("Length must be {MIN_LEN}-{MAX_LEN}"));
+        }
+        if self.pronounceable {
+            return Ok(());
+        }
+        let classes = self.classes();
+        let on: Vec<&Class> = classes.iter().filter(|c| c.min.is_some()).collect();
+        if on.is_empty() {
+            return Err("Enable at least one character class".to_string());
+        }
+        let mut need = 0;
+        for c in &on {
+            let min = c.min.unwrap_or(0);
+            if c.chars.is_empty() {
+                return Err(format!("No {} left to choose from", c.name));
+            }
+            if self.no_repeat && min > c.chars.len() {
+                return Err(format!(
+                    "Only {} distinct {} without repeats",
+                    c.chars.len(),
+                    c.name
+                ));
+            }
+            need += min;
+        }
+        if need > self.len {
+            return Err(format!(
+                "Minimums add up to {need}, more than the length {}",
+                self.len
+            ));
+        }
+        let pool: usize = on.iter().map(|c| c.chars.len()).sum();
+        if self.no_repeat && pool < self.len {
+            return Err(format!("Only {pool} distinct characters without repeats"));
+        }
+        Ok(())
+    }
+
+    /// Characters a passphrase insertion draws from, never the separator.
+    pub fn insert_chars(&self) -> Vec<char> {
+        let mut chars: Vec<char> = ('0'..='9').collect();
+        chars.extend(self.classes().swap_remove(3).chars);
+        chars.retain(|c| !self.separator.contains(*c));
+        chars
+    }
+
+    /// Bits of entropy of a generated password: exact for passphrases, an
+    /// upper bound for characters since minimums narrow the choice slightly.
+    pub fn entropy_bits(&self) -> f64 {
+        let log2 = |n: usize| (n.max(1) as f64).log2();
+        match self.mode() {
+            Mode::Words => {
+                let n = self.words.unwrap_or_default();
+                let mut bits = n as f64 * log2(eff_words().len());
+                if self.insert {
+                    bits += log2(n) + log2(self.insert_chars().len());
+                }
+                return bits;
+            }
+            Mode::Pattern => {
+                return self
+                    .pattern_sets()
+                    .map_or(0.0, |sets| sets.iter().map(|s| log2(s.len())).sum());
+            }
+            Mode::Pronounceable => {
+                return (0..self.len)
+                    .map(|i| log2(if i % 2 == 0 { CONSONANTS } else { VOWELS }.len()))
+                    .sum();
+            }
+            Mode::Chars => {}
+        }
+        let pool: usize = self
+            .classes()
+            .iter()
+            .filter(|c| c.min.is_some())
+            .map(|c| c.chars.len())
+            .sum();
+        (0..self.len)
+            .map(|i| {
+                let left = if self.no_repeat {
+                    pool - i.min(pool)
+                } else {
+                    pool
+                };
+                (left.max(1) as f64).log2()
+            })
+            .sum()
+    }
+
+    /// One-line description, e.g. `20 chars · a-z · A-Z ≥2 · 0-9 · no repeats`.
+    pub fn summary(&self) -> String {
+        if let Some(ref pattern) = self.pattern {
+            return format!("pattern {pattern}");
+        }
+        if self.pronounceable {
+            return format!("{} chars · pronounceable", self.len);
+        }
+        if let Some(n) = self.words {
+            let mut parts = vec![format!("{n} words"), format!("'{}'", self.separator)];
+            if self.capitalize {
+                parts.push("capitalized".to_string());
+            }
+            if self.insert {
+                parts.push("digit/symbol".to_string());
+            }
+            return parts.join(" · ");
+        }
+        let mut parts = vec![format!("{} chars", self.len)];
+        for (label, min) in [
+            ("a-z", self.lower),
+            ("A-Z", self.upper),
+            ("0-9", self.digits),
+            ("symbols", self.symbols),
+        ] {
+            match min {
+                Some(n) if n > 1 => parts.push(format!("{label} ≥{n}")),
+                Some(_) => parts.push(label.to_string()),
+                None => {}
+            }
+        }
+        if self.symbols.is_some() && !self.symbol_set.is_empty() {
+            parts.push(format!("from {}", self.symbol_set));
+        }
+        if self.no_ambiguous {
+            parts.push("no ambiguous".to_string());
+        }
+        if self.no_repeat {
+            parts.push("no repeats".to_string());
+        }
+        parts.join(" · ")
+    }
+
+    /// Applies generator options from `args`, returning what was not
+    /// recognized.
+    pub fn apply_args(&mut self, args: &[String]) -> Result<Vec<String>, String> {
+        let mut rest = Vec::new();
+        let mut it = args.iter();
+        let num = |v: Option<&String>, name: &str| -> Result<usize, String> {
+            v.and_then(|v| v.parse().ok())
+                .ok_or_else(|| format!("{name} needs a number"))
+        };
+        while let Some(arg) = it.next() {
+            match arg.as_str() {
+                "--length" | "-l" => self.len = num(it.next(), arg)?,
+                "--no-lower" => self.lower = None,
+                "--no-upper" => self.upper = None,
+                "--no-digits" => self.digits = None,
+                "--no-symbols" => self.symbols = None,
+                "--min-lower" => self.lower = Some(num(it.next(), arg)?),
+                "--min-upper" => self.upper = Some(num(it.next(), arg)?),
+                "--min-digits" => self.digits = Some(num(it.next(), arg)?),
+                "--min-symbols" => self.symbols = Some(num(it.next(), arg)?),
+                "--symbols" => {
+                    self.symbol_set = it.next().ok_or("--symbols needs a set")?.clone();
+                    self.symbols.get_or_insert(1);
+                }
+                "--no-ambiguous" => self.no_ambiguous = true,
+                "--no-repeat" => self.no_repeat = true,
+                "--words" => self.words = Some(num(it.next(), arg)?),
+                "--separator" => {
+                    self.separator = it.next().ok_or("--separator needs a value")?.clone();
+                }
+                "--capitalize" => self.capitalize = true,
+                "--insert" => self.insert = true,
+                "--pronounceable" => self.pronounceable = true,
+                "--pattern" => {
+                    self.pattern = Some(it.next().ok_or("--pattern needs a value")?.clone());
+                }
+                _ => rest.push(arg.clone()),
+            }
+        }
+        Ok(rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_policy() {
+        let p = Policy {
+            len: 12,
+            lower: None,
+            digits: Some(4),
+            symbols: Some(2),
+            symbol_set: "#!".to_string(),
+            no_ambiguous: true,
+            no_repeat: true,
+            ..Policy::default()
+        };
+        for _ in 0..50 {
+            let pwd = crate::crypto::gen_pwd(&p).unwrap();
+            assert_eq!(pwd.chars().count(), 12);
+            assert!(!pwd.chars().any(|c| c.is_ascii_lowercase()));
+            assert!(pwd.chars().filter(char::is_ascii_digit).count() >= 4);
+            assert_eq!(pwd.chars().filter(|c| "#!".contains(*c)).count(), 2);
+            assert!(!pwd.chars().any(|c| AMBIGUOUS.contains(c)));
+            let mut seen: Vec<char> = pwd.chars().collect();
+            seen.sort_unstable();
+            seen.dedup();
+            assert_eq!(seen.len(), 12);
+        }
+
+        let digits_only = Policy {
+            len: 11,
+            lower: None,
+            upper: None,
+            symbols: None,
+            no_repeat: true,
+            ..Policy::default()
+        };
+        assert!(crate::crypto::gen_pwd(&digits_only).is_err());
+        let too_many = Policy {
+            len: 4,
+            digits: Some(3),
+            ..Policy::default()
+        };
+        assert!(too_many.validate().is_err());
+    }
+
+    #[test]
+    fn test_passphrase() {
+        let words = eff_words();
+        assert_eq!(words.len(), 7776);
+        assert_eq!(words.iter().collect::<HashSet<_>>().len(), 7776);
+        assert!(
+            (Policy {
+                words: Some(6),
+                ..Policy::default()
+            }
+            .entropy_bits()
+                - 77.5)
+                .abs()
+                < 0.1
+        );
+
+        if crate::crypto::init_crypto().is_err() {
+            println!("Skipping passphrase test - libsodium not available");
+            return;
+        }
+        let p = Policy {
+            words: Some(5),
+            separator: ".".to_string(),
+            capitalize: true,
+            insert: true,
+            ..Policy::default()
+        };
+        let pwd = crate::crypto::gen_pwd(&p).unwrap();
+        let parts: Vec<&str> = pwd.split('.').collect();
+        assert_eq!(parts.len(), 5);
+        assert!(
+            parts
+                .iter()
+                .filter(|w| w.starts_with(char::is_uppercase))
+                .count()
+                >= 4
+        );
+        let plain = parts
+            .iter()
+            .filter(|w| words.contains(&w.to_lowercase().as_str()))
+            .count();
+        assert!(plain >= 4);
+    }
+
+    #[test]
+    fn test_pattern() {
+        let sets = parse_pattern("Cvc-9{2}[A-C]\\d", &['!']).unwrap();
+        assert_eq!(sets.len(), 8);
+        assert_eq!(sets[3], vec!['-']);
+        assert_eq!(sets[4].len(), 10);
+        assert_eq!(sets[6], vec!['A', 'B', 'C']);
+        assert_eq!(sets[7], vec!['d']);
+        assert_eq!(parse_pattern("[A-F0-9]{32}", &[]).unwrap().len(), 32);
+        for bad in ["", "[ab", "{3}", "a{x}", "[z-a]", "a{999}", "\\"] {
+            assert!(parse_pattern(bad, &[]).is_err(), "{bad}");
+        }
+
+        let mut p = Policy::default();
+        p.set_mode(Mode::Pattern);
+        assert_eq!(p.mode(), Mode::Pattern);
+        assert_eq!(p.pattern.as_deref(), Some(DEFAULT_PATTERN));
+        p.pattern = Some("dddd".to_string());
+        assert!((p.entropy_bits() - 4.0 * 10f64.log2()).abs() < 1e-9);
+        p.set_mode(Mode::Pronounceable);
+        assert!(p.pattern.is_none() && p.pronounceable);
+
+        if crate::crypto::init_crypto().is_err() {
+            println!("Skipping pattern test - libsodium not available");
+            return;
+        }
+        let hex = Policy {
+            pattern: Some("[A-F0-9]{32}".to_string()),
+            ..Policy::default()
+        };
+        let pwd = crate::crypto::gen_pwd(&hex).unwrap();
+        assert_eq!(pwd.len(), 32);
+        assert!(pwd.chars().all(|c| matches!(c, 'A'..='F' | '0'..='9')));
+        p.len = 12;
+        let pwd: Vec<char> = crate::crypto::gen_pwd(&p).unwrap().chars().collect();
+        assert_eq!(pwd.len(), 12);
+        for (i, c) in pwd.iter().enumerate() {
+            assert!(if i % 2 == 0 { CONSONANTS } else { VOWELS }.contains(*c));
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! Fuzzy entry search. Query terms match as subsequences ("gthb" finds
+//! "github"), optionally scoped with `name:`, `user:`, `url:` or `tag:`, and
+//! every term has to match. Results rank by match quality, then recency.
+
+use crate::models::Entry;
+
+/// Which part of an entry a query term is matched against.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scope {
+    Any,
+    Name,
+    User,
+    Url,
+    Tag,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Term {
+    pub scope: Scope,
+    pub text: Vec<char>,
+}
+
+/// Splits a query on whitespace into terms, reading `scope:` prefixes.
+/// Terms left empty, such as a bare `tag:`, are dropped.
+pub fn parse(query: &str) -> Vec<Term> {
+    query
+        .split_whitespace()
+        .map(|word| {
+            let (scope, text) = match word.split_once(':') {
+                Some(("name" | "n", t)) => (Scope::Name, t),
+                Some(("user" | "u", t)) => (Scope::User, t),
+                Some(("url", t)) => (Scope::Url, t),
+                Some(("tag" | "t", t)) => (Scope::Tag, t),
+                _ => (Scope::Any, word),
+            };
+            Term {
+                scope,
+                text: fold(text),
+            }
+        })
+        .filter(|t| !t.text.is_empty())
+        .collect()
+}
+
+/// Lowercases one char to one char, so match positions index the original.
+fn fold(s: &str) -> Vec<char> {
+    s.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+/// Letters and digits present in `s` as bits, for a cheap "can it match"
+/// test before scoring.
+fn mask(s: &[char]) -> u64 {
+    s.iter().fold(0, |m, &c| {
+        m | match c {
+            'a'..='z' => 1 << (c as u32 - 'a' as u32),
+            '0'..='9' => 1 << (26 + c as u32 - '0' as u32),
+            _ => 1 << 36,
+        }
+    })
+}
+
+/// Scores `needle` as a subsequence of `hay`, both folded, returning the
+/// score and the matched positions in `hay`. Consecutive characters and
+/// word starts earn bonuses; gaps cost a little.
+pub fn fuzzy(needle: &[char], hay: &[char]) -> Option<(i64, Vec<usize>)> {
+    if needle.is_empty() || needle.len() > hay.len() {
+        return None;
+    }
+    let word_start = |i: usize| i == 0 || !hay[i - 1].is_alphanumeric();
+    // Prefer a contiguous run, earliest word start first.
+    let runs: Vec<usize> = (0..=hay.len() - needle.len())
+        .filter(|&i| hay[i..].starts_with(needle))
+        .collect();
+    let start = runs
+        .iter()
+        .find(|&&i| word_start(i))
+        .or_else(|| runs.first())
+        .copied();
+    let pos: Vec<usize> = match start {
+        Some(i) => (i..i + needle.len()).collect(),
+        None => {
+            let mut pos = Vec::with_capacity(needle.len());
+            let mut it = hay.iter().enumerate();
+            for &c in needle {
+                pos.push(it.find(|&(_, &h)| h == c)?.0);
+            }
+            pos
+        }
+    };
+    let mut score = 0;
+    for (k, &i) in pos.iter().enumerate() {
+        score += 16;
+        if word_start(i) {
+            score += if k == 0 { 20 } else { 8 };
+        }
+        if k > 0 {
+            let gap = i - pos[k - 1] - 1;
+            if gap == 0 {
+                score += 24;
+            } else {
+                score -= (gap as i64).min(8);
+            }
+        }
+    }
+    if pos[0] == 0 {
+        score += 12;
+    }
+    if pos.len() == hay.len() {
+        score += 32;
+    }
+    Some((score, pos))
+}
+
+/// One entry's searchable text, folded once when the index is built.
+struct Doc {
+    name: Vec<char>,
+    user: Vec<char>,
+    url: Vec<char>,
+    tags: Vec<Vec<char>>,
+    /// Non-secret schema and custom fields, matched only by unscoped terms.
+    other: Vec<Vec<char>>,
+    mask: u64,
+    modified: u64,
+}
+
+/// A ranked search result: the entry's index in the slice the `Index` was
+/// built from and where the query matched its name and username.
+#[derive(Debug)]
+pub struct Hit {
+    pub idx: usize,
+    pub score: i64,
+    pub name: Vec<usize>,
+    pub user: Vec<usize>,
+}
+
+pub struct Index {
+    docs: Vec<Doc>,
+}
+
+impl Index {
+    pub fn new(entries: &[Entry]) -> Self {
+        let docs = entries
+            .iter()
+            .map(|e| {
+                let mut other: Vec<Vec<char>> = e
+                    .kind
+                    .schema()
+                    .iter()
+                    .filter(|f| !f.secret && f.key != "u" && e.data.contains_key(f.key))
+                    .map(|f| fold(e.get_field(f.key)))
+                    .collect();
+                for f in e.fields.iter().filter(|f| !f.kind.is_secret()) {
+                    other.push(fold(&f.name));
+                    other.push(fold(&f.value));
+                }
+                let mut doc = Doc {
+                    name: fold(&e.n),
+                    user: fold(&e.u),
+                    url: fold(e.url.as_deref().unwrap_or_default()),
+                    tags: e.tags.iter().map(|t| fold(t)).collect(),
+                    other,
+                    mask: 0,
+                    modified: e.last_modified,
+                };
+                doc.mask = [&doc.name, &doc.user, &doc.url]
+                    .into_iter()
+                    .chain(&doc.tags)
+                    .chain(&doc.other)
+                    .fold(0, |m, s| m | mask(s));
+                doc
+            })
+            .collect();
+        Self { docs }
+    }
+
+    /// Ranks the entries matching every term of `query`, best first. `now`
+    /// is used for the recency bonus.
+    pub fn search(&self, query: &str, now: u64) -> Vec<Hit> {
+        let terms = parse(query);
+        let need = terms.iter().fold(0, |m, t| m | mask(&t.text));
+        let mut hits: Vec<Hit> = self
+            .docs
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| d.mask & need == need)
+            .filter_map(|(idx, d)| {
+                let mut hit = Hit {
+                    idx,
+                    score: 0,
+                    name: Vec::new(),
+                    user: Vec::new(),
+                };
+                for t in &terms {
+                    hit.score += d.score(t, &mut hit)?;
+                }
+                hit.name.sort_unstable();
+                hit.name.dedup();
+                hit.user.sort_unstable();
+                hit.user.dedup();
+                // Up to 20 points for entries touched recently, halving
+                // every 30 days.
+                let days = now.saturating_sub(d.modified) / 86400;
+                hit.score += 20 >> (days / 30).min(20);
+                Some(hit)
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| self.docs[a.idx].name.cmp(&self.docs[b.idx].name))
+        });
+        hits
+    }
+}
+
+impl Doc {
+    /// The best score of `t` over the fields it may match, recording name
+    /// and username positions for highlighting.
+    fn score(&self, t: &Term, hit: &mut Hit) -> Option<i64> {
+        let best = |fields: &mut dyn Iterator<Item = &Vec<char>>| {
+            fields
+                .filter_map(|f| fuzzy(&t.text, f))
+                .map(|(s, _)| s)
+                .max()
+        };
+        let name = matches!(t.scope, Scope::Any | Scope::Name)
+            .then(|| fuzzy(&t.text, &self.name))
+            .flatten();
+        let user = matches!(t.scope, Scope::Any | Scope::User)
+            .then(|| fuzzy(&t.text, &self.user))
+            .flatten();
+        let url = matches!(t.scope, Scope::Any | Scope::Url)
+            .then(|| fuzzy(&t.text, &self.url))
+            .flatten()
+            .map(|(s, _)| s);
+        let tag = matches!(t.scope, Scope::Any | Scope::Tag)
+            .then(|| best(&mut self.tags.iter()))
+            .flatten();
+        let other = (t.scope == Scope::Any)
+            .then(|| best(&mut self.other.iter()))
+            .flatten();
+        // A name match counts double: it is what people usually type.
+        let score = [
+            name.as_ref().map(|(s, _)| s * 2),
+            user.as_ref().map(|(s, _)| *s),
+            url,
+            tag,
+            other.map(|s| s / 2),
+        ]
+        .into_iter()
+        .flatten()
+        .max()?;
+        if let Some((_, pos)) = name {
+            hit.name.extend(pos);
+        }
+        if let Some((_, pos)) = user {
+            hit.user.extend(pos);
+        }
+        Some(score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(n: &str, u: &str, url: Option<&str>, tags: &[&str], modified: u64) -> Entry {
+        serde_json::from_value(serde_json::json!({
+            "id": n, "n": n, "u": u, "p": "", "url": url, "t": 0,
+            "tags": tags, "last_modified": modified,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_search() {
+        assert_eq!(
+            parse("tag:prod  user:admin url: gh"),
+            vec![
+                Term {
+                    scope: Scope::Tag,
+                    text: fold("prod")
+                },
+                Term {
+                    scope: Scope::User,
+                    text: fold("admin")
+                },
+                Term {
+                    scope: Scope::Any,
+                    text: fold("gh")
+                },
+            ]
+        );
+        let (_, pos) = fuzzy(&fold("gthb"), &fold("GitHub")).unwrap();
+        assert_eq!(pos, vec![0, 2, 3, 5]);
+        assert!(fuzzy(&fold("hubg"), &fold("github")).is_none());
+        // A contiguous word-start match beats a scattered one.
+        let run = fuzzy(&fold("hub"), &fold("git hub")).unwrap().0;
+        let scattered = fuzzy(&fold("hub"), &fold("h-u-b-x")).unwrap().0;
+        assert!(run > scattered);
+
+        let day = 86400;
+        let now = 1000 * day;
+        let entries = vec![
+            entry("GitHub", "admin", Some("https://github.com"), &["dev"], now),
+            entry("Gitea", "admin", None, &["prod"], now - 400 * day),
+            entry("Hub", "me", None, &["prod"], now - 400 * day),
+            entry("Mail", "root", None, &[], now),
+        ];
+        let index = Index::new(&entries);
+        let names = |q: &str| -> Vec<&str> {
+            index
+                .search(q, now)
+                .iter()
+                .map(|h| entries[h.idx].n.as_str())
+                .collect()
+        };
+        assert_eq!(names("gthb"), vec!["GitHub"]);
+        assert_eq!(names("hub")[0], "Hub");
+        assert_eq!(names("tag:prod user:admin"), vec!["Gitea"]);
+        assert_eq!(names("url:github"), vec!["GitHub"]);
+        assert!(names("url:gitea").is_empty());
+        // Same match quality: the recently modified entry ranks first.
+        assert_eq!(names("user:admin"), vec!["GitHub", "Gitea"]);
+        let hit = &index.search("gh adm", now)[0];
+        assert_eq!(
+            (hit.name.clone(), hit.user.clone()),
+            (vec![0, 3], vec![0, 1, 2])
+        );
+    }
+}
@@ -1,13 +1,33 @@
 use crate::crypto;
 use crate::models::Vault;
-use std::fs;
+use serde::Deserialize;
+use std::fs::{self, OpenOptions, Permissions};
+use std::io::Write;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
 use std::path::PathBuf;
 
-fn vt_p() -> PathBuf {
+/// Plaintext prefix of the vault file, readable without the master password.
+pub struct VaultHeader {
+    pub salt: Vec<u8>,
+}
+
+pub fn vt_p() -> PathBuf {
     let home = dirs::home_dir().expect("no home");
     home.join(".passlock.vault")
 }
 
+/// Directory holding encrypted attachment blobs, one file per blob.
+pub fn blobs_p() -> PathBuf {
+    let home = dirs::home_dir().expect("no home");
+    home.join(".passlock.blobs")
+}
+
+/// Plaintext UI preferences; never holds anything secret.
+pub fn cfg_p() -> PathBuf {
+    let home = dirs::home_dir().expect("no home");
+    home.join(".passlock.config")
+}
+
 fn tmp_p() -> PathBuf {
     let home = dirs::home_dir().expect("no home");
     home.join(".passlock.temp")
@@ -24,15 +44,10 @@ pub fn svv(v: &Vault, pwd: &str) -> Result<(), String> {
     final_data.extend_from_slice(&salt_bytes);
     final_data.extend_from_slice(&enc_d);
 
-    fs::write(vt_p(), final_data).map_err(|e| e.to_string())?;
-
-    let tmp_j = serde_json::to_string(v).map_err(|e| e.to_string())?;
-    fs::write(tmp_p(), tmp_j).map_err(|e| e.to_string())?;
-
-    Ok(())
+    fs::write(vt_p(), final_data).map_err(|e| e.to_string())
 }
 
-pub fn ld_vt(pwd: &str) -> Result<Vault, String> {
+fn dec_vt(pwd: &str) -> Result<Vec<u8>, String> {
     let data = fs::read(vt_p()).map_err(|_| "vault not found")?;
 
     if data.len() < 16 {
@@ -43,17 +58,76 @@ pub fn ld_vt(pwd: &str) -> Result<Vault, String> {
     let enc_data = &data[16..];
     let salt = hex::encode(salt_bytes);
 
-    let dec_data = crypto::dec(enc_data, pwd, &salt)?;
-    let dec_str = String::from_utf8(dec_data).map_err(|_| "invalid data")?;
+    crypto::dec(enc_data, pwd, &salt)
+}
 
-    let v: Vault = serde_json::from_str(&dec_str).map_err(|e| e.to_string())?;
+pub fn ld_vt(pwd: &str) -> Result<Vault, String> {
+    let dec_data = dec_vt(pwd)?;
+    let dec_str = String::from_utf8(dec_data).map_err(|_| "invalid data")?;
 
-    let tmp_j = serde_json::to_string(&v).map_err(|e| e.to_string())?;
-    fs::write(tmp_p(), tmp_j).map_err(|e| e.to_string())?;
+    let mut v: Vault = serde_json::from_str(&dec_str).map_err(|e| e.to_string())?;
+    if v.migrate_ids() > 0 {
+        svv(&v, pwd)?;
+    }
 
     Ok(v)
 }
 
+/// Vault-level fields, read without building any entry.
+#[derive(Deserialize)]
+pub struct VaultMeta {
+    pub s: String,
+    #[serde(default)]
+    pub created: u64,
+}
+
+/// Checks `pwd` and reads the vault metadata. The decrypted JSON is
+/// zeroed before returning.
+pub fn ld_meta(pwd: &str) -> Result<VaultMeta, String> {
+    let mut dec_data = dec_vt(pwd)?;
+    let meta = serde_json::from_slice(&dec_data).map_err(|e| e.to_string());
+    crypto::secure_wipe(&mut dec_data);
+    meta
+}
+
+/// Writes the decrypted vault to the plaintext temp file the web server
+/// reads after `passlock unlock`, readable by the owner only.
+pub fn wr_tmp(v: &Vault) -> Result<(), String> {
+    let tmp_j = serde_json::to_string(v).map_err(|e| e.to_string())?;
+    let path = tmp_p();
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&path)
+        .map_err(|e| e.to_string())?;
+    // `mode` only applies when the file is created.
+    fs::set_permissions(&path, Permissions::from_mode(0o600)).map_err(|e| e.to_string())?;
+    file.write_all(tmp_j.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Overwrites and deletes the plaintext copy `wr_tmp` leaves behind, if
+/// there is one.
+pub fn rm_tmp() -> Result<(), String> {
+    let path = tmp_p();
+    let Ok(meta) = fs::metadata(&path) else {
+        return Ok(());
+    };
+    fs::write(&path, vec![0u8; meta.len() as usize]).map_err(|e| e.to_string())?;
+    fs::remove_file(&path).map_err(|e| e.to_string())
+}
+
 pub fn vt_exi() -> bool {
     vt_p().exists()
 }
+
+pub fn rd_hdr() -> Result<VaultHeader, String> {
+    let data = fs::read(vt_p()).map_err(|_| "vault not found")?;
+    if data.len() < 16 {
+        return Err("corrupt vault".to_string());
+    }
+    Ok(VaultHeader {
+        salt: data[0..16].to_vec(),
+    })
+}
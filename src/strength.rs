@@ -0,0 +1,843 @@
+//! Password strength estimation in the spirit of zxcvbn: the password is
+//! split into the cheapest sequence of guessable patterns (dictionary words,
+//! keyboard walks, repeats, sequences, dates) and bruteforced gaps, and the
+//! guesses needed for that sequence drive the score.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Anything longer is only scored on its first `MAX_LEN` characters.
+const MAX_LEN: usize = 100;
+const BRUTEFORCE_CARDINALITY: f64 = 10.0;
+const MIN_GUESSES_SINGLE: f64 = 10.0;
+const MIN_GUESSES_MULTI: f64 = 50.0;
+/// Extra cost for every additional pattern in the sequence.
+const MIN_GUESSES_BEFORE_GROWING: f64 = 10_000.0;
+const REFERENCE_YEAR: i32 = 2025;
+const MIN_YEAR_SPACE: f64 = 20.0;
+/// Guesses per second against a slow (Argon2) hash with one attacker box.
+const GUESSES_PER_SEC: f64 = 10_000.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pattern {
+    Dictionary {
+        list: &'static str,
+        rank: usize,
+        l33t: bool,
+        reversed: bool,
+    },
+    Spatial {
+        turns: usize,
+    },
+    Repeat {
+        base: String,
+    },
+    Sequence,
+    Date,
+    Bruteforce,
+}
+
+#[derive(Clone, Debug)]
+pub struct Match {
+    pub i: usize,
+    pub j: usize,
+    pub token: String,
+    pub pattern: Pattern,
+    pub guesses: f64,
+}
+
+#[derive(Debug)]
+pub struct Estimate {
+    pub guesses: f64,
+    pub sequence: Vec<Match>,
+}
+
+impl Estimate {
+    pub fn guesses_log10(&self) -> f64 {
+        self.guesses.log10()
+    }
+
+    /// Seconds an offline attacker needs on average.
+    pub fn crack_secs(&self) -> f64 {
+        self.guesses / GUESSES_PER_SEC
+    }
+
+    pub fn crack_time(&self) -> String {
+        fmt_duration(self.crack_secs())
+    }
+
+    /// The weakest pattern found, if any, as a warning naming its kind, plus
+    /// suggestions. Neither quotes the password, so both are safe to show
+    /// under a masked input.
+    pub fn feedback(&self) -> (Option<String>, Vec<String>) {
+        let mut suggestions = Vec::new();
+        let Some(m) = self
+            .sequence
+            .iter()
+            .filter(|m| m.pattern != Pattern::Bruteforce)
+            .max_by_key(|m| m.token.chars().count())
+        else {
+            if self.guesses < 1e10 {
+                suggestions.push("Use a longer password".to_string());
+            }
+            return (None, suggestions);
+        };
+        let tok = &m.token;
+        let warning = match &m.pattern {
+            Pattern::Dictionary {
+                list,
+                rank,
+                l33t,
+                reversed,
+            } => {
+                if tok.chars().next().is_some_and(char::is_uppercase) {
+                    suggestions.push("Capitalization doesn't help very much".to_string());
+                }
+                if *l33t {
+                    suggestions.push(
+                        "Predictable substitutions like '@' instead of 'a' don't help very much"
+                            .to_string(),
+                    );
+                }
+                if *reversed {
+                    suggestions.push("Reversed words aren't much harder to guess".to_string());
+                }
+                match *list {
+                    "passwords" if *rank <= 10 && !l33t => {
+                        "This is a top-10 common password".to_string()
+                    }
+                    "passwords" if *rank <= 100 && !l33t => {
+                        "This is a top-100 common password".to_string()
+                    }
+                    "passwords" if *l33t => {
+                        "This is similar to a commonly used password".to_string()
+                    }
+                    "passwords" => "This is a very common password".to_string(),
+                    "user_inputs" => {
+                        "Part of it is taken from this entry's own details".to_string()
+                    }
+                    _ => "A dictionary word is easy to guess on its own".to_string(),
+                }
+            }
+            Pattern::Spatial { turns } => {
+                suggestions.push("Use a longer keyboard pattern with more turns".to_string());
+                if *turns == 1 {
+                    "Straight rows of keys are easy to guess".to_string()
+                } else {
+                    "Short keyboard patterns are easy to guess".to_string()
+                }
+            }
+            Pattern::Repeat { base } => {
+                suggestions.push("Avoid repeated words and characters".to_string());
+                if base.chars().count() == 1 {
+                    "Repeated characters like \"aaa\" are easy to guess".to_string()
+                } else {
+                    "Repeats like \"abcabc\" are barely harder to guess than the part repeated"
+                        .to_string()
+                }
+            }
+            Pattern::Sequence => {
+                suggestions.push("Avoid sequences".to_string());
+                "Sequences like abc or 6543 are easy to guess".to_string()
+            }
+            Pattern::Date => {
+                suggestions.push("Avoid dates and years that are associated with you".to_string());
+                "Dates are easy to guess".to_string()
+            }
+            Pattern::Bruteforce => unreachable!(),
+        };
+        suggestions.push("Avoid common patterns; add another uncommon word or two".to_string());
+        (Some(warning), suggestions)
+    }
+}
+
+fn ranked(list: &'static str) -> HashMap<String, usize> {
+    let mut map = HashMap::new();
+    for (i, w) in list
+        .lines()
+        .map(str::trim)
+        .filter(|w| !w.is_empty())
+        .enumerate()
+    {
+        map.entry(w.to_string()).or_insert(i + 1);
+    }
+    map
+}
+
+fn dictionaries() -> &'static [(&'static str, HashMap<String, usize>)] {
+    static DICTS: OnceLock<Vec<(&'static str, HashMap<String, usize>)>> = OnceLock::new();
+    DICTS.get_or_init(|| {
+        vec![
+            ("passwords", ranked(include_str!("data/passwords.txt"))),
+            ("words", ranked(include_str!("data/words.txt"))),
+        ]
+    })
+}
+
+fn n_ck(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    (1..=k).fold(1.0, |acc, d| acc * (n + 1 - d) as f64 / d as f64)
+}
+
+fn uppercase_variations(token: &str) -> f64 {
+    let upper = token.chars().filter(|c| c.is_uppercase()).count();
+    let lower = token.chars().filter(|c| c.is_lowercase()).count();
+    if upper == 0 {
+        return 1.0;
+    }
+    let first_only = token.chars().next().is_some_and(char::is_uppercase) && upper == 1;
+    let last_only = token.chars().last().is_some_and(char::is_uppercase) && upper == 1;
+    if lower == 0 || first_only || last_only {
+        return 2.0;
+    }
+    (1..=upper.min(lower)).map(|k| n_ck(upper + lower, k)).sum()
+}
+
+const L33T: &[(char, &[char])] = &[
+    ('a', &['4', '@']),
+    ('b', &['8']),
+    ('e', &['3']),
+    ('g', &['6', '9']),
+    ('i', &['1', '!', '|']),
+    ('l', &['1', '|', '7']),
+    ('o', &['0']),
+    ('s', &['$', '5']),
+    ('t', &['+', '7']),
+    ('z', &['2']),
+];
+
+/// Every way of reading the l33t characters in `token` as letters, capped so
+/// a string of digits doesn't explode.
+fn unl33t(token: &[char]) -> Vec<Vec<char>> {
+    let mut out = vec![Vec::with_capacity(token.len())];
+    for &c in token {
+        let letters: Vec<char> = L33T
+            .iter()
+            .filter(|(_, subs)| subs.contains(&c))
+            .map(|(l, _)| *l)
+            .collect();
+        if letters.is_empty() {
+            out.iter_mut().for_each(|v| v.push(c));
+            continue;
+        }
+        let mut next = Vec::new();
+        for v in &out {
+            for &l in &letters {
+                let mut v = v.clone();
+                v.push(l);
+                next.push(v);
+            }
+            if next.len() > 64 {
+                break;
+            }
+        }
+        out = next;
+    }
+    out
+}
+
+fn l33t_variations(token: &[char], plain: &[char]) -> f64 {
+    let mut variations = 1.0;
+    let mut seen = Vec::new();
+    for (t, p) in token.iter().zip(plain) {
+        let t = t.to_ascii_lowercase();
+        if t == *p || seen.contains(&(t, *p)) {
+            continue;
+        }
+        seen.push((t, *p));
+        let subbed = token.iter().filter(|&&c| c == t).count();
+        let unsubbed = token
+            .iter()
+            .filter(|c| c.to_ascii_lowercase() == *p)
+            .count();
+        variations *= if unsubbed == 0 {
+            2.0
+        } else {
+            (1..=subbed.min(unsubbed))
+                .map(|k| n_ck(subbed + unsubbed, k))
+                .sum()
+        };
+    }
+    variations
+}
+
+fn dictionary_matches(pw: &[char], user: &HashMap<String, usize>) -> Vec<Match> {
+    let mut out = Vec::new();
+    let lists = dictionaries()
+        .iter()
+        .map(|(name, map)| (*name, map))
+        .chain(std::iter::once(("user_inputs", user)));
+    for (list, dict) in lists {
+        for i in 0..pw.len() {
+            for j in i + 2..pw.len().min(i + 32) {
+                let token = &pw[i..=j];
+                let lower: Vec<char> = token.iter().map(|c| c.to_ascii_lowercase()).collect();
+                let tok: String = token.iter().collect();
+                let upper = uppercase_variations(&tok);
+                let forward: String = lower.iter().collect();
+                if let Some(&rank) = dict.get(&forward) {
+                    out.push(Match {
+                        i,
+                        j,
+                        token: tok.clone(),
+                        pattern: Pattern::Dictionary {
+                            list,
+                            rank,
+                            l33t: false,
+                            reversed: false,
+                        },
+                        guesses: rank as f64 * upper,
+                    });
+                }
+                let backward: String = lower.iter().rev().collect();
+                if backward != forward {
+                    if let Some(&rank) = dict.get(&backward) {
+                        out.push(Match {
+                            i,
+                            j,
+                            token: tok.clone(),
+                            pattern: Pattern::Dictionary {
+                                list,
+                                rank,
+                                l33t: false,
+                                reversed: true,
+                            },
+                            guesses: rank as f64 * upper * 2.0,
+                        });
+                    }
+                }
+                if !lower
+                    .iter()
+                    .any(|c| L33T.iter().any(|(_, s)| s.contains(c)))
+                {
+                    continue;
+                }
+                let best = unl33t(&lower)
+                    .into_iter()
+                    .filter(|plain| *plain != lower)
+                    .filter_map(|plain| {
+                        let word: String = plain.iter().collect();
+                        dict.get(&word)
+                            .map(|&rank| (rank, l33t_variations(&lower, &plain)))
+                    })
+                    .min_by(|a, b| (a.0 as f64 * a.1).total_cmp(&(b.0 as f64 * b.1)));
+                if let Some((rank, l33t)) = best {
+                    out.push(Match {
+                        i,
+                        j,
+                        token: tok,
+                        pattern: Pattern::Dictionary {
+                            list,
+                            rank,
+                            l33t: true,
+                            reversed: false,
+                        },
+                        guesses: rank as f64 * upper * l33t,
+                    });
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Key positions on a slanted QWERTY layout, unshifted and shifted.
+fn qwerty() -> &'static HashMap<char, (i32, i32, bool)> {
+    static KEYS: OnceLock<HashMap<char, (i32, i32, bool)>> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        let rows: [(&str, &str, i32); 4] = [
+            ("`1234567890-=", "~!@#$%^&*()_+", 0),
+            ("qwertyuiop[]\\", "QWERTYUIOP{}|", 1),
+            ("asdfghjkl;'", "ASDFGHJKL:\"", 1),
+            ("zxcvbnm,./", "ZXCVBNM<>?", 1),
+        ];
+        let mut keys = HashMap::new();
+        for (y, (plain, shifted, start)) in rows.iter().enumerate() {
+            for (x, (p, s)) in plain.chars().zip(shifted.chars()).enumerate() {
+                keys.insert(p, (*start + x as i32, y as i32, false));
+                keys.insert(s, (*start + x as i32, y as i32, true));
+            }
+        }
+        keys
+    })
+}
+
+/// Direction from key `a` to key `b` if they are neighbours, 0..6.
+fn qwerty_dir(a: char, b: char) -> Option<usize> {
+    let keys = qwerty();
+    let (ax, ay, _) = keys.get(&a)?;
+    let (bx, by, _) = keys.get(&b)?;
+    [(-1, 0), (0, -1), (1, -1), (1, 0), (0, 1), (-1, 1)]
+        .iter()
+        .position(|(dx, dy)| ax + dx == *bx && ay + dy == *by)
+}
+
+fn keypad_dir(a: char, b: char) -> Option<usize> {
+    const PAD: [&str; 4] = ["789", "456", "123", "0"];
+    let pos = |c: char| {
+        PAD.iter()
+            .enumerate()
+            .find_map(|(y, r)| r.find(c).map(|x| (x as i32, y as i32)))
+    };
+    let ((ax, ay), (bx, by)) = (pos(a)?, pos(b)?);
+    let (dx, dy) = (bx - ax, by - ay);
+    if (dx, dy) == (0, 0) || dx.abs() > 1 || dy.abs() > 1 {
+        return None;
+    }
+    Some(((dy + 1) * 3 + dx + 1) as usize)
+}
+
+fn spatial_guesses(len: usize, turns: usize, shifted: usize, keypad: bool) -> f64 {
+    let (starts, degree) = if keypad { (10.0, 4.0) } else { (94.0, 4.6) };
+    let mut guesses = 0.0;
+    for i in 2..=len {
+        for j in 1..=turns.min(i - 1) {
+            guesses += n_ck(i - 1, j - 1) * starts * f64::powi(degree, j as i32);
+        }
+    }
+    if shifted > 0 {
+        let unshifted = len - shifted;
+        guesses *= if unshifted == 0 {
+            2.0
+        } else {
+            (1..=shifted.min(unshifted))
+                .map(|k| n_ck(shifted + unshifted, k))
+                .sum()
+        };
+    }
+    guesses
+}
+
+fn spatial_matches(pw: &[char]) -> Vec<Match> {
+    let mut out = Vec::new();
+    for keypad in [false, true] {
+        let dir = if keypad { keypad_dir } else { qwerty_dir };
+        let mut i = 0;
+        while i + 2 < pw.len() {
+            let mut j = i;
+            let mut turns = 0;
+            let mut last = None;
+            while j + 1 < pw.len() {
+                let Some(d) = dir(pw[j], pw[j + 1]) else {
+                    break;
+                };
+                if last != Some(d) {
+                    turns += 1;
+                    last = Some(d);
+                }
+                j += 1;
+            }
+            if j - i >= 2 {
+                let shifted = if keypad {
+                    0
+                } else {
+                    pw[i..=j]
+                        .iter()
+                        .filter(|c| qwerty().get(c).is_some_and(|k| k.2))
+                        .count()
+                };
+                out.push(Match {
+                    i,
+                    j,
+                    token: pw[i..=j].iter().collect(),
+                    pattern: Pattern::Spatial { turns },
+                    guesses: spatial_guesses(j - i + 1, turns, shifted, keypad),
+                });
+                i = j + 1;
+            } else {
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Runs of one base string repeated, ignoring case (`Aaaaaaaa` repeats `a`).
+fn repeat_matches(pw: &[char], user: &HashMap<String, usize>) -> Vec<Match> {
+    let lower: Vec<char> = pw.iter().map(|c| c.to_ascii_lowercase()).collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < pw.len() {
+        let mut best: Option<(usize, usize)> = None;
+        for base_len in 1..=(pw.len() - i) / 2 {
+            let base = &lower[i..i + base_len];
+            let mut n = 1;
+            while lower[i + n * base_len..].starts_with(base) {
+                n += 1;
+            }
+            if n >= 2 && best.is_none_or(|(bl, bn)| base_len * n > bl * bn) {
+                best = Some((base_len, n));
+            }
+        }
+        let Some((base_len, n)) = best else {
+            i += 1;
+            continue;
+        };
+        let j = i + base_len * n - 1;
+        let token: String = pw[i..=j].iter().collect();
+        let base: String = lower[i..i + base_len].iter().collect();
+        let base_guesses = estimate_chars(&lower[i..i + base_len], user).guesses;
+        out.push(Match {
+            i,
+            j,
+            guesses: base_guesses * n as f64 * uppercase_variations(&token),
+            token,
+            pattern: Pattern::Repeat { base },
+        });
+        i = j + 1;
+    }
+    out
+}
+
+fn sequence_matches(pw: &[char]) -> Vec<Match> {
+    let class = |c: char| {
+        if c.is_ascii_lowercase() {
+            1
+        } else if c.is_ascii_uppercase() {
+            2
+        } else if c.is_ascii_digit() {
+            3
+        } else {
+            0
+        }
+    };
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i + 2 < pw.len() {
+        let delta = pw[i + 1] as i32 - pw[i] as i32;
+        if delta == 0 || delta.abs() > 5 || class(pw[i]) == 0 || class(pw[i]) != class(pw[i + 1]) {
+            i += 1;
+            continue;
+        }
+        let mut j = i + 1;
+        while j + 1 < pw.len()
+            && pw[j + 1] as i32 - pw[j] as i32 == delta
+            && class(pw[j + 1]) == class(pw[i])
+        {
+            j += 1;
+        }
+        if j - i >= 2 {
+            let first = pw[i];
+            let base = if matches!(first, 'a' | 'A' | 'z' | 'Z' | '0' | '1' | '9') {
+                4.0
+            } else if first.is_ascii_digit() {
+                10.0
+            } else {
+                26.0
+            };
+            let dir = if delta < 0 { 2.0 } else { 1.0 };
+            out.push(Match {
+                i,
+                j,
+                token: pw[i..=j].iter().collect(),
+                pattern: Pattern::Sequence,
+                guesses: base * (j - i + 1) as f64 * dir,
+            });
+            i = j;
+        } else {
+            i += 1;
+        }
+    }
+    out
+}
+
+fn year_guesses(year: i32) -> f64 {
+    f64::from((year - REFERENCE_YEAR).abs()).max(MIN_YEAR_SPACE)
+}
+
+fn expand_year(y: i32, digits: usize) -> Option<i32> {
+    match (digits, y) {
+        (2, 0..=49) => Some(2000 + y),
+        (2, 50..=99) => Some(1900 + y),
+        (4, 1900..=2049) => Some(y),
+        _ => None,
+    }
+}
+
+/// Tries `parts` as day/month/year in any of the usual orders.
+fn valid_date(parts: &[(i32, usize); 3]) -> Option<i32> {
+    let orders = [[0, 1, 2], [1, 0, 2], [2, 1, 0], [2, 0, 1]];
+    orders.iter().find_map(|o| {
+        let (a, b, (y, yd)) = (parts[o[0]].0, parts[o[1]].0, parts[o[2]]);
+        let year = expand_year(y, yd)?;
+        let day_month = (1..=31).contains(&a) && (1..=12).contains(&b);
+        let month_day = (1..=12).contains(&a) && (1..=31).contains(&b);
+        (day_month || month_day).then_some(year)
+    })
+}
+
+fn date_matches(pw: &[char]) -> Vec<Match> {
+    let mut out = Vec::new();
+    let num = |s: &[char]| s.iter().collect::<String>().parse::<i32>().ok();
+    for i in 0..pw.len() {
+        for j in i + 3..pw.len().min(i + 10) {
+            let token = &pw[i..=j];
+            let text: String = token.iter().collect();
+            let mut year = None;
+            let mut separated = false;
+            if token.iter().all(char::is_ascii_digit) {
+                let n = token.len();
+                if n == 4 {
+                    year = num(token).and_then(|y| expand_year(y, 4));
+                }
+                // 4 to 8 digits split into three parts of 1, 2 or 4 digits.
+                for a in 1..n {
+                    for b in a + 1..n {
+                        if year.is_some() {
+                            break;
+                        }
+                        let parts = [(0, a), (a, b), (b, n)];
+                        if parts.iter().any(|(s, e)| !matches!(e - s, 1 | 2 | 4)) {
+                            continue;
+                        }
+                        let parsed: Option<Vec<(i32, usize)>> = parts
+                            .iter()
+                            .map(|&(s, e)| num(&token[s..e]).map(|v| (v, e - s)))
+                            .collect();
+                        if let Some(p) = parsed {
+                            year = valid_date(&[p[0], p[1], p[2]]);
+                        }
+                    }
+                }
+            } else {
+                let seps: Vec<usize> = (0..token.len())
+                    .filter(|&k| matches!(token[k], '/' | '-' | '.' | '_' | ' ' | '\\'))
+                    .collect();
+                if let [s1, s2] = seps[..] {
+                    let same_sep = token[s1] == token[s2];
+                    let parts = [(0, s1), (s1 + 1, s2), (s2 + 1, token.len())];
+                    let parsed: Option<Vec<(i32, usize)>> = parts
+                        .iter()
+                        .map(|&(s, e)| {
+                            let p = &token[s..e];
+                            (matches!(p.len(), 1 | 2 | 4) && p.iter().all(char::is_ascii_digit))
+                                .then(|| num(p).map(|v| (v, p.len())))
+                                .flatten()
+                        })
+                        .collect();
+                    if let (true, Some(p)) = (same_sep, parsed) {
+                        year = valid_date(&[p[0], p[1], p[2]]);
+                        separated = true;
+                    }
+                }
+            }
+            let Some(year) = year else { continue };
+            let mut guesses = year_guesses(year);
+            if text.len() > 4 {
+                guesses *= 365.0;
+            }
+            if separated {
+                guesses *= 4.0;
+            }
+            out.push(Match {
+                i,
+                j,
+                token: text,
+                pattern: Pattern::Date,
+                guesses,
+            });
+        }
+    }
+    out
+}
+
+fn factorial(n: usize) -> f64 {
+    (2..=n).map(|k| k as f64).product()
+}
+
+/// Picks the sequence of matches covering `pw` that needs the fewest
+/// guesses, filling gaps with bruteforce.
+fn most_guessable(pw: &[char], mut matches: Vec<Match>) -> Estimate {
+    let n = pw.len();
+    if n == 0 {
+        return Estimate {
+            guesses: 1.0,
+            sequence: Vec::new(),
+        };
+    }
+    for m in &mut matches {
+        let min = if m.j == m.i {
+            MIN_GUESSES_SINGLE
+        } else {
+            MIN_GUESSES_MULTI
+        };
+        if m.j - m.i + 1 < n {
+            m.guesses = m.guesses.max(min);
+        }
+    }
+    for i in 0..n {
+        for j in i..n {
+            let len = (j - i + 1) as i32;
+            let mut guesses = BRUTEFORCE_CARDINALITY.powi(len);
+            if len < n as i32 {
+                guesses = guesses.max(if len == 1 {
+                    MIN_GUESSES_SINGLE + 1.0
+                } else {
+                    MIN_GUESSES_MULTI + 1.0
+                });
+            }
+            matches.push(Match {
+                i,
+                j,
+                token: pw[i..=j].iter().collect(),
+                pattern: Pattern::Bruteforce,
+                guesses,
+            });
+        }
+    }
+    // best[k][l]: lowest product of guesses covering pw[..=k] with l
+    // matches, and the match ending that cover.
+    let mut best: Vec<HashMap<usize, (f64, usize)>> = vec![HashMap::new(); n];
+    let mut by_end: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (idx, m) in matches.iter().enumerate() {
+        by_end[m.j].push(idx);
+    }
+    for k in 0..n {
+        for &idx in &by_end[k] {
+            let m = &matches[idx];
+            let prev: Vec<(usize, f64)> = if m.i == 0 {
+                vec![(0, 1.0)]
+            } else {
+                best[m.i - 1].iter().map(|(l, (pi, _))| (*l, *pi)).collect()
+            };
+            for (l, pi) in prev {
+                let cand = pi * m.guesses;
+                let slot = best[k].entry(l + 1).or_insert((f64::INFINITY, idx));
+                if cand < slot.0 {
+                    *slot = (cand, idx);
+                }
+            }
+        }
+    }
+    let total =
+        |l: usize, pi: f64| factorial(l) * pi + MIN_GUESSES_BEFORE_GROWING.powi(l as i32 - 1);
+    let (&l, _) = best[n - 1]
+        .iter()
+        .min_by(|a, b| total(*a.0, a.1 .0).total_cmp(&total(*b.0, b.1 .0)))
+        .expect("bruteforce always covers the password");
+    let guesses = total(l, best[n - 1][&l].0);
+
+    let mut sequence = Vec::new();
+    let (mut k, mut l) = (n - 1, l);
+    loop {
+        let idx = best[k][&l].1;
+        let m = matches[idx].clone();
+        let start = m.i;
+        sequence.push(m);
+        if start == 0 {
+            break;
+        }
+        k = start - 1;
+        l -= 1;
+    }
+    sequence.reverse();
+    Estimate { guesses, sequence }
+}
+
+fn estimate_chars(pw: &[char], user: &HashMap<String, usize>) -> Estimate {
+    let mut matches = dictionary_matches(pw, user);
+    matches.extend(spatial_matches(pw));
+    matches.extend(repeat_matches(pw, user));
+    matches.extend(sequence_matches(pw));
+    matches.extend(date_matches(pw));
+    most_guessable(pw, matches)
+}
+
+/// Estimates how many guesses `password` takes. `user_inputs` are words an
+/// attacker could know, like the entry's name or username.
+pub fn estimate(password: &str, user_inputs: &[&str]) -> Estimate {
+    let pw: Vec<char> = password.chars().take(MAX_LEN).collect();
+    let mut user = HashMap::new();
+    for (i, w) in user_inputs
+        .iter()
+        .flat_map(|s| s.split(|c: char| !c.is_alphanumeric()))
+        .filter(|w| w.chars().count() >= 3)
+        .enumerate()
+    {
+        user.entry(w.to_lowercase()).or_insert(i + 1);
+    }
+    estimate_chars(&pw, &user)
+}
+
+/// Rough human duration, e.g. `3 hours` or `centuries`.
+pub fn fmt_duration(secs: f64) -> String {
+    const UNITS: [(f64, &str); 6] = [
+        (60.0, "second"),
+        (3600.0, "minute"),
+        (86_400.0, "hour"),
+        (86_400.0 * 31.0, "day"),
+        (86_400.0 * 365.0, "month"),
+        (86_400.0 * 365.0 * 100.0, "year"),
+    ];
+    const DIVS: [f64; 6] = [
+        1.0,
+        60.0,
+        3600.0,
+        86_400.0,
+        86_400.0 * 31.0,
+        86_400.0 * 365.0,
+    ];
+    if secs < 1.0 {
+        return "less than a second".to_string();
+    }
+    for ((limit, unit), div) in UNITS.iter().zip(DIVS) {
+        if secs < *limit {
+            let n = (secs / div).round() as u64;
+            return format!("{n} {unit}{}", if n == 1 { "" } else { "s" });
+        }
+    }
+    "centuries".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(pw: &str) -> Vec<Pattern> {
+        estimate(pw, &[])
+            .sequence
+            .into_iter()
+            .map(|m| m.pattern)
+            .collect()
+    }
+
+    #[test]
+    fn test_matchers() {
+        assert!(matches!(
+            patterns("p@ssw0rd")[..],
+            [Pattern::Dictionary { l33t: true, .. }]
+        ));
+        assert!(matches!(
+            patterns("drowssap")[..],
+            [Pattern::Dictionary { reversed: true, .. }]
+        ));
+        assert!(matches!(patterns("zxcvfr")[..], [Pattern::Spatial { .. }]));
+        assert!(matches!(patterns("Aaaaaaaa")[..], [Pattern::Repeat { .. }]));
+        assert_eq!(patterns("lmnopq"), [Pattern::Sequence]);
+        assert_eq!(patterns("13/04/1987"), [Pattern::Date]);
+    }
+
+    #[test]
+    fn test_guess_order() {
+        let weak = estimate("Aaaaaaaa1!", &[]).guesses;
+        let strong = estimate("tK8#qz!Vw2^mL", &[]).guesses;
+        assert!(weak < 1e6, "{weak}");
+        assert!(strong > 1e12, "{strong}");
+        let own = estimate("alice2024", &["alice@example.com"]);
+        assert!(own.feedback().0.unwrap().contains("entry's own details"));
+        for pw in ["monkey", "13/04/1987", "qwertyuiop", "zzzzzzzz", "lmnopq"] {
+            let (warning, suggestions) = estimate(pw, &[]).feedback();
+            assert!(warning.is_some(), "{pw}");
+            assert!(warning.iter().chain(&suggestions).all(|f| !f.contains(pw)));
+        }
+    }
+
+    #[test]
+    fn test_fmt_duration() {
+        assert_eq!(fmt_duration(0.5), "less than a second");
+        assert_eq!(fmt_duration(7200.0), "2 hours");
+        assert_eq!(fmt_duration(1e12), "centuries");
+    }
+}
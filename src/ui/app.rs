@@ -1,8 +1,18 @@
-use super::screens::{InputField, MessageType, Screen};
+use super::screens::{
+    FolderOp, FormSlot, GenRow, InputField, MessageType, Screen, TagOp, TrashOp, TreeRow,
+    GEN_CHAR_ROWS, GEN_PATTERN_ROWS, GEN_PRON_ROWS, GEN_WORD_ROWS,
+};
+use crate::attachments;
+use crate::audit::{self, Finding, DEFAULT_MAX_AGE};
+use crate::clipboard;
+use crate::config::{self, Config};
 use crate::crypto;
-use crate::models::{Entry, PasswordHistory, Vault};
+use crate::models::{norm_folder, CustomField, Entry, EntryKind, FieldKind, HistField, Vault};
+use crate::policy::{Mode, Policy, DEFAULT_WORDS, MAX_LEN, MAX_WORDS, MIN_LEN, MIN_WORDS};
+use crate::search::{self, Hit};
 use crate::storage;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::time::{Duration, Instant};
 
 pub struct App {
     pub screen: Screen,
@@ -18,21 +28,64 @@ pub struct App {
     pub msg_type: MessageType,
     pub entry_disp: Vec<Entry>,
     pub search_query: String,
+    /// Built when the search screen opens; see `search_entries`.
+    pub search_idx: Option<search::Index>,
+    /// Where each search result matched, in `entry_disp` order.
+    pub search_hits: Vec<Hit>,
     pub gen_pwd: String,
-    #[allow(dead_code)]
+    pub gen_policy: Policy,
+    pub gen_sel: usize,
+    /// The form the generator was opened from, if any.
+    pub gen_ret: Option<Screen>,
+    /// First entry shown by the scrolled entry lists, kept by the renderer.
     pub scroll_offset: usize,
+    /// How many entries the last rendered list fit on one page.
+    pub page_len: usize,
+    pub n_entry_kind: EntryKind,
     pub n_entry_name: String,
     pub n_entry_user: String,
     pub n_entry_pass: String,
     pub n_entry_url: String,
     pub n_entry_notes: String,
+    pub n_entry_data: BTreeMap<String, String>,
     pub n_entry_tags: Vec<String>,
     pub tag_input: String,
+    pub n_entry_fields: Vec<CustomField>,
+    pub field_input: String,
+    pub field_kind: FieldKind,
+    /// Custom field being edited in place; `None` while adding a new one.
+    pub field_sel: Option<usize>,
     pub add_fi: usize,
     pub all_tags: Vec<(String, usize)>,
     pub select_tf: usize,
     pub active_tf: Option<String>,
     pub edit_eid: String,
+    pub n_entry_folder: String,
+    pub folder_open: HashSet<String>,
+    pub folder_sel: usize,
+    pub folder_op: Option<FolderOp>,
+    pub tag_sel: usize,
+    pub tag_marked: Vec<String>,
+    pub tag_op: Option<TagOp>,
+    pub config: Config,
+    pub n_entry_rotate: String,
+    pub n_entry_policy: Option<Policy>,
+    pub due_sel: usize,
+    pub hist_sel: usize,
+    pub trash_sel: usize,
+    pub trash_op: Option<TrashOp>,
+    /// Entry waiting for a y/N before the delete screen trashes it.
+    pub del_confirm: Option<usize>,
+    pub audit: Vec<Finding>,
+    pub audit_sel: usize,
+    /// When to clear the clipboard, and the digest of what we put there.
+    pub clip: Option<(Instant, clipboard::Tag)>,
+    /// Use counters bumped since the vault was last written.
+    pub uses_dirty: bool,
+    /// The entry whose secrets are unmasked, and until when.
+    pub reveal: Option<(String, Instant)>,
+    /// Time of the last key press, for the idle lock.
+    pub last_input: Instant,
 }
 
 impl App {
@@ -51,20 +104,53 @@ impl App {
             msg_type: MessageType::None,
             entry_disp: Vec::new(),
             search_query: String::new(),
+            search_idx: None,
+            search_hits: Vec::new(),
             gen_pwd: String::new(),
+            gen_policy: Policy::default(),
+            gen_sel: 0,
+            gen_ret: None,
             scroll_offset: 0,
+            page_len: 1,
+            n_entry_kind: EntryKind::Login,
             n_entry_name: String::new(),
             n_entry_user: String::new(),
             n_entry_pass: String::new(),
             n_entry_url: String::new(),
             n_entry_notes: String::new(),
+            n_entry_data: BTreeMap::new(),
             n_entry_tags: Vec::new(),
             tag_input: String::new(),
+            n_entry_fields: Vec::new(),
+            field_input: String::new(),
+            field_kind: FieldKind::Text,
+            field_sel: None,
             add_fi: 0,
             all_tags: Vec::new(),
             select_tf: 0,
             active_tf: None,
             edit_eid: String::new(),
+            n_entry_folder: String::new(),
+            folder_open: HashSet::new(),
+            folder_sel: 0,
+            folder_op: None,
+            tag_sel: 0,
+            tag_marked: Vec::new(),
+            tag_op: None,
+            config: config::load(),
+            n_entry_rotate: String::new(),
+            n_entry_policy: None,
+            due_sel: 0,
+            hist_sel: 0,
+            trash_sel: 0,
+            trash_op: None,
+            del_confirm: None,
+            audit: Vec::new(),
+            audit_sel: 0,
+            clip: None,
+            uses_dirty: false,
+            reveal: None,
+            last_input: Instant::now(),
         }
     }
 
@@ -91,7 +177,13 @@ impl App {
             self.set_msg("Passwords don't match!", MessageType::Error);
             return;
         }
-        let salt = crypto::gen_salt();
+        let salt = match crypto::gen_salt() {
+            Ok(salt) => salt,
+            Err(e) => {
+                self.set_msg(&e, MessageType::Error);
+                return;
+            }
+        };
         let vault = Vault::new(salt);
         match storage::svv(&vault, &self.input_buffer) {
             Ok(()) => {
@@ -111,17 +203,20 @@ impl App {
 
     pub fn unlock_vault(&mut self) {
         match storage::ld_vt(&self.input_buffer) {
-            Ok(vault) => {
+            Ok(mut vault) => {
                 self.master_pwd = self.input_buffer.clone();
+                if vault.purge_expired(crate::get_timestamp()) > 0
+                    && storage::svv(&vault, &self.input_buffer).is_ok()
+                {
+                    let _ = attachments::gc(&vault);
+                }
                 self.vault = Some(vault);
                 self.screen = Screen::MainMenu;
                 self.input_buffer.clear();
                 self.input_field = InputField::None;
                 self.set_msg("Vault unlocked!", MessageType::Success);
                 self.load_at();
-                if let Some(ref vault) = self.vault {
-                    self.entry_disp = vault.e.clone();
-                }
+                self.show_all();
             }
             Err(_) => {
                 self.set_msg("Wrong password!", MessageType::Error);
@@ -129,92 +224,139 @@ impl App {
         }
     }
 
-    pub fn add_entry(&mut self) {
-        if self.n_entry_name.is_empty()
-            || self.n_entry_user.is_empty()
-            || self.n_entry_pass.is_empty()
-        {
-            self.set_msg(
-                "Name, Username, and Password are required!",
-                MessageType::Error,
-            );
-            return;
+    /// Focusable rows of the add/edit form for the selected entry type. Tags
+    /// sit just above the notes so logins keep their familiar order.
+    pub fn form_slots(&self) -> Vec<FormSlot> {
+        let schema = self.n_entry_kind.schema();
+        let mut slots = vec![FormSlot::Kind, FormSlot::Name, FormSlot::Folder];
+        slots.extend(schema.iter().filter(|f| f.key != "nt").map(FormSlot::Field));
+        slots.push(FormSlot::Tags);
+        slots.push(FormSlot::Rotate);
+        slots.extend(schema.iter().filter(|f| f.key == "nt").map(FormSlot::Field));
+        slots.push(FormSlot::Fields);
+        slots
+    }
+
+    pub fn cur_slot(&self) -> FormSlot {
+        self.form_slots()
+            .get(self.add_fi)
+            .copied()
+            .unwrap_or(FormSlot::Name)
+    }
+
+    pub fn form_val(&self, key: &str) -> &str {
+        match key {
+            "u" => &self.n_entry_user,
+            "p" => &self.n_entry_pass,
+            "url" => &self.n_entry_url,
+            "nt" => &self.n_entry_notes,
+            _ => self.n_entry_data.get(key).map_or("", String::as_str),
+        }
+    }
+
+    pub fn form_val_mut(&mut self, key: &str) -> &mut String {
+        match key {
+            "u" => &mut self.n_entry_user,
+            "p" => &mut self.n_entry_pass,
+            "url" => &mut self.n_entry_url,
+            "nt" => &mut self.n_entry_notes,
+            _ => self.n_entry_data.entry(key.to_string()).or_default(),
+        }
+    }
+
+    /// Copies the form into `entry`, keeping only the fields of the chosen
+    /// type's schema.
+    fn apply_form(&self, entry: &mut Entry) {
+        entry.n.clone_from(&self.n_entry_name);
+        entry.folder = norm_folder(&self.n_entry_folder);
+        entry.rotate_days = self.n_entry_rotate.parse().ok().filter(|d| *d > 0);
+        entry.kind = self.n_entry_kind.clone();
+        entry.u.clear();
+        entry.p.clear();
+        entry.url = None;
+        entry.nt = None;
+        entry.otp = None;
+        entry.data.clear();
+        for f in self.n_entry_kind.schema() {
+            entry.set_field(f.key, self.form_val(f.key));
         }
+        entry.tags.clone_from(&self.n_entry_tags);
+        entry.fields.clone_from(&self.n_entry_fields);
+        entry.policy.clone_from(&self.n_entry_policy);
+    }
+
+    pub fn add_entry(&mut self) {
         let now = crate::get_timestamp();
-        let entry = Entry {
+        let mut entry = Entry {
             id: crate::generate_uuid(),
-            n: self.n_entry_name.clone(),
-            u: self.n_entry_user.clone(),
-            p: self.n_entry_pass.clone(),
-            url: if self.n_entry_url.is_empty() {
-                None
-            } else {
-                Some(self.n_entry_url.clone())
-            },
-            nt: if self.n_entry_notes.is_empty() {
-                None
-            } else {
-                Some(self.n_entry_notes.clone())
-            },
+            n: String::new(),
+            u: String::new(),
+            p: String::new(),
+            url: None,
+            nt: None,
             t: now,
-            tags: self.n_entry_tags.clone(),
+            tags: Vec::new(),
             history: Vec::new(),
             last_modified: now,
+            fields: Vec::new(),
+            kind: EntryKind::Login,
+            data: BTreeMap::new(),
+            otp: None,
+            attachments: Vec::new(),
+            folder: None,
+            favorite: false,
+            uses: 0,
+            rotate_days: None,
+            deleted: None,
+            breached: None,
+            policy: None,
+            pwd_changed_at: None,
         };
+        self.apply_form(&mut entry);
+        if let Err(e) = entry.validate() {
+            self.set_msg(&e, MessageType::Error);
+            return;
+        }
         if let Some(ref mut vault) = self.vault {
             vault.e.push(entry);
             if let Err(e) = storage::svv(vault, &self.master_pwd) {
                 self.set_msg(&format!("Failed to save: {e}"), MessageType::Error);
             } else {
-                self.set_msg("Password added successfully!", MessageType::Success);
+                self.set_msg("Entry added successfully!", MessageType::Success);
                 self.ca_form();
                 self.screen = Screen::MainMenu;
                 self.load_at();
-                if let Some(ref vault) = self.vault {
-                    self.entry_disp = vault.e.clone();
-                }
+                self.show_all();
             }
         }
     }
 
     pub fn edit_entry(&mut self) {
-        if self.n_entry_name.is_empty()
-            || self.n_entry_user.is_empty()
-            || self.n_entry_pass.is_empty()
-        {
-            self.set_msg(
-                "Name, Username, and Password are required!",
-                MessageType::Error,
-            );
+        let Some(mut updated) = self
+            .vault
+            .as_ref()
+            .and_then(|v| v.e.iter().find(|e| e.id == self.edit_eid))
+            .cloned()
+        else {
+            return;
+        };
+        let old = updated.clone();
+        self.apply_form(&mut updated);
+        if let Err(e) = updated.validate() {
+            self.set_msg(&e, MessageType::Error);
             return;
         }
+        let depth = self.vault.as_ref().map_or(0, |v| v.history_depth);
+        for field in [HistField::Password, HistField::Username, HistField::Url] {
+            let before = old.get_field(field.key());
+            if before != updated.get_field(field.key()) {
+                updated.push_history(field, before.to_string(), depth, false);
+            }
+        }
+        updated.last_modified = crate::get_timestamp();
         if let Some(ref mut vault) = self.vault {
             if let Some(entry) = vault.e.iter_mut().find(|e| e.id == self.edit_eid) {
-                let now = crate::get_timestamp();
-                if entry.p != self.n_entry_pass {
-                    if entry.history.len() >= 5 {
-                        entry.history.remove(0);
-                    }
-                    entry.history.push(PasswordHistory {
-                        password: entry.p.clone(),
-                        changed_at: now,
-                    });
-                }
-                entry.n.clone_from(&self.n_entry_name);
-                entry.u.clone_from(&self.n_entry_user);
-                entry.p.clone_from(&self.n_entry_pass);
-                entry.url = if self.n_entry_url.is_empty() {
-                    None
-                } else {
-                    Some(self.n_entry_url.clone())
-                };
-                entry.nt = if self.n_entry_notes.is_empty() {
-                    None
-                } else {
-                    Some(self.n_entry_notes.clone())
-                };
-                entry.tags.clone_from(&self.n_entry_tags);
-                entry.last_modified = now;
+                *entry = updated;
 
                 if let Err(e) = storage::svv(vault, &self.master_pwd) {
                     self.set_msg(&format!("Failed to save: {e}"), MessageType::Error);
@@ -223,9 +365,7 @@ impl App {
                     self.ca_form();
                     self.screen = Screen::MainMenu;
                     self.load_at();
-                    if let Some(ref vault) = self.vault {
-                        self.entry_disp = vault.e.clone();
-                    }
+                    self.show_all();
                 }
             }
         }
@@ -235,12 +375,22 @@ impl App {
         if let Some(ref vault) = self.vault {
             if let Some(entry) = vault.e.iter().find(|e| e.id == entry_id) {
                 self.edit_eid = entry.id.clone();
+                self.n_entry_kind = entry.kind.clone();
                 self.n_entry_name = entry.n.clone();
+                self.n_entry_folder = entry.folder.clone().unwrap_or_default();
+                self.n_entry_rotate = entry.rotate_days.map_or(String::new(), |d| d.to_string());
                 self.n_entry_user = entry.u.clone();
                 self.n_entry_pass = entry.p.clone();
                 self.n_entry_url = entry.url.clone().unwrap_or_default();
                 self.n_entry_notes = entry.nt.clone().unwrap_or_default();
+                self.n_entry_data = entry.data.clone();
+                if let Some(ref otp) = entry.otp {
+                    self.n_entry_data.insert("otp".to_string(), otp.clone());
+                }
                 self.n_entry_tags = entry.tags.clone();
+                self.n_entry_fields = entry.fields.clone();
+                self.field_sel = None;
+                self.n_entry_policy = entry.policy.clone();
                 self.add_fi = 0;
                 self.screen = Screen::EditPassword;
             }
@@ -250,16 +400,18 @@ impl App {
     pub fn delete_entry(&mut self, index: usize) {
         if let Some(ref mut vault) = self.vault {
             if index < vault.e.len() {
-                let removed = vault.e.remove(index);
+                let id = vault.e[index].id.clone();
+                let name = vault.trash_entry(&id).unwrap_or_default();
                 if let Err(e) = storage::svv(vault, &self.master_pwd) {
                     self.set_msg(&format!("Failed to save: {e}"), MessageType::Error);
                 } else {
-                    self.set_msg(&format!("Deleted '{}'", removed.n), MessageType::Success);
+                    self.set_msg(
+                        &format!("Moved '{name}' to the trash"),
+                        MessageType::Success,
+                    );
                     self.screen = Screen::MainMenu;
                     self.load_at();
-                    if let Some(ref vault) = self.vault {
-                        self.entry_disp = vault.e.clone();
-                    }
+                    self.show_all();
                 }
             } else {
                 self.set_msg("Invalid entry number!", MessageType::Error);
@@ -267,36 +419,560 @@ impl App {
         }
     }
 
+    /// Restores or purges the trash entry under the cursor.
+    pub fn apply_trash(&mut self, purge: bool) {
+        let Some(mut vault) = self.vault.take() else {
+            return;
+        };
+        let Some(id) = vault.trash.get(self.trash_sel).map(|e| e.id.clone()) else {
+            self.vault = Some(vault);
+            return;
+        };
+        let result = if purge {
+            vault
+                .purge_entry(&id, crate::get_timestamp())
+                .map(|n| format!("Purged '{n}'"))
+                .ok_or_else(|| "Entry is not in the trash".to_string())
+        } else {
+            vault.restore_entry(&id).map(|n| format!("Restored '{n}'"))
+        };
+        match result.and_then(|m| storage::svv(&vault, &self.master_pwd).map(|()| m)) {
+            Ok(m) => {
+                if purge {
+                    // Blobs are only reachable through entries; a failed
+                    // sweep just leaves garbage for the next one.
+                    let _ = attachments::gc(&vault);
+                }
+                self.set_msg(&m, MessageType::Success);
+            }
+            Err(e) => self.set_msg(&e, MessageType::Error),
+        }
+        self.trash_sel = self.trash_sel.min(vault.trash.len().saturating_sub(1));
+        self.vault = Some(vault);
+        self.load_at();
+        self.show_all();
+    }
+
+    pub fn set_trash_days(&mut self) {
+        let Ok(days) = self.input_buffer.trim().parse::<u32>() else {
+            self.set_msg("Enter a number of days (0 = never)", MessageType::Error);
+            return;
+        };
+        let Some(ref mut vault) = self.vault else {
+            return;
+        };
+        vault.trash_days = days;
+        vault.purge_expired(crate::get_timestamp());
+        if let Err(e) = storage::svv(vault, &self.master_pwd) {
+            self.set_msg(&format!("Failed to save: {e}"), MessageType::Error);
+            return;
+        }
+        let _ = attachments::gc(vault);
+        self.trash_sel = self.trash_sel.min(vault.trash.len().saturating_sub(1));
+        self.trash_op = None;
+        self.input_buffer.clear();
+        self.set_msg(
+            &if days == 0 {
+                "Trash is kept until purged by hand".to_string()
+            } else {
+                format!("Trash is purged after {days} days")
+            },
+            MessageType::Success,
+        );
+    }
+
+    /// Ranks the vault against the query as typed. An empty query lists
+    /// everything in the current sort order.
     pub fn search_entries(&mut self) {
+        let Some(ref vault) = self.vault else {
+            return;
+        };
+        if self.search_query.trim().is_empty() {
+            self.entry_disp = vault.e.clone();
+            self.search_hits.clear();
+            self.sort_disp();
+            return;
+        }
+        let index = self
+            .search_idx
+            .get_or_insert_with(|| search::Index::new(&vault.e));
+        self.search_hits = index.search(&self.search_query, crate::get_timestamp());
+        self.entry_disp = self
+            .search_hits
+            .iter()
+            .map(|h| vault.e[h.idx].clone())
+            .collect();
+    }
+
+    /// Shows every entry in the current sort order.
+    pub fn show_all(&mut self) {
         if let Some(ref vault) = self.vault {
-            let query = self.search_query.to_lowercase();
-            if query.is_empty() {
-                self.entry_disp = vault.e.clone();
+            self.entry_disp = vault.e.clone();
+        }
+        self.sort_disp();
+    }
+
+    pub fn sort_disp(&mut self) {
+        self.config.sort.apply(&mut self.entry_disp);
+    }
+
+    /// Copies the selected entry's password, or its username, and counts
+    /// it as a use.
+    pub fn copy_sel(&mut self, user: bool) {
+        let Some(entry) = self.entry_disp.get(self.selected_entry) else {
+            return;
+        };
+        let (what, value) = if user {
+            ("Username", &entry.u)
+        } else {
+            ("Password", &entry.p)
+        };
+        if value.is_empty() {
+            self.set_msg(
+                &format!("{what} is empty for {}", entry.n),
+                MessageType::Error,
+            );
+            return;
+        }
+        if let Err(e) = clipboard::copy(value) {
+            self.set_msg(&e, MessageType::Error);
+            return;
+        }
+        let id = entry.id.clone();
+        let secs = self.config.clip_secs();
+        self.clip = (secs > 0).then(|| {
+            (
+                Instant::now() + Duration::from_secs(secs),
+                clipboard::tag(value),
+            )
+        });
+        // Saving re-derives the key, too slow to do on every copy. The
+        // counter rides along with the next save, or `flush_uses`.
+        if let Some(e) = self
+            .vault
+            .as_mut()
+            .and_then(|v| v.e.iter_mut().find(|e| e.id == id))
+        {
+            e.uses += 1;
+            self.uses_dirty = true;
+        }
+        for e in self.entry_disp.iter_mut().filter(|e| e.id == id) {
+            e.uses += 1;
+        }
+        self.set_msg(
+            &if secs > 0 {
+                format!("{what} copied, clearing in {secs}s")
             } else {
-                self.entry_disp = vault
-                    .e
-                    .iter()
-                    .filter(|e| {
-                        e.n.to_lowercase().contains(&query)
-                            || e.u.to_lowercase().contains(&query)
-                            || e.url
-                                .as_ref()
-                                .is_some_and(|u| u.to_lowercase().contains(&query))
-                            || e.tags.iter().any(|t| t.to_lowercase().contains(&query))
-                    })
-                    .cloned()
-                    .collect();
+                format!("{what} copied")
+            },
+            MessageType::Success,
+        );
+    }
+
+    /// Writes out use counters no other save has picked up yet. Called
+    /// when locking, which exiting also does.
+    pub fn flush_uses(&mut self) {
+        if !self.uses_dirty {
+            return;
+        }
+        self.uses_dirty = false;
+        if let Some(ref vault) = self.vault {
+            let _ = storage::svv(vault, &self.master_pwd);
+        }
+    }
+
+    /// Whether the secrets of entry `id` may be shown: never in presentation
+    /// mode, otherwise while a reveal is running. The add/edit form passes
+    /// `edit_eid`, which is empty for a new entry.
+    pub fn shown(&self, id: &str) -> bool {
+        !self.config.present
+            && self
+                .reveal
+                .as_ref()
+                .is_some_and(|(r, until)| r == id && Instant::now() < *until)
+    }
+
+    /// Unmasks entry `id` for a while, or masks it again.
+    pub fn toggle_reveal(&mut self, id: &str) {
+        if self.config.present {
+            self.set_msg("Presentation mode is on", MessageType::Error);
+        } else if self.shown(id) {
+            self.reveal = None;
+        } else {
+            let secs = self.config.reveal_secs();
+            self.reveal = Some((id.to_string(), Instant::now() + Duration::from_secs(secs)));
+            self.set_msg(&format!("Revealed for {secs}s"), MessageType::Info);
+        }
+    }
+
+    pub fn toggle_compact(&mut self) {
+        self.config.compact = !self.config.compact;
+        if let Err(e) = config::save(&self.config) {
+            self.set_msg(&format!("Failed to save config: {e}"), MessageType::Error);
+        }
+    }
+
+    pub fn reveal_sel(&mut self) {
+        if let Some(id) = self
+            .entry_disp
+            .get(self.selected_entry)
+            .map(|e| e.id.clone())
+        {
+            self.toggle_reveal(&id);
+        }
+    }
+
+    pub fn toggle_present(&mut self) {
+        self.config.present = !self.config.present;
+        self.reveal = None;
+        match config::save(&self.config) {
+            Ok(()) => self.set_msg(
+                if self.config.present {
+                    "Presentation mode on: all secrets hidden"
+                } else {
+                    "Presentation mode off"
+                },
+                MessageType::Info,
+            ),
+            Err(e) => self.set_msg(&format!("Failed to save config: {e}"), MessageType::Error),
+        }
+    }
+
+    /// Forgets the unlocked vault and the master password and goes back to
+    /// the unlock screen. Secrets still in memory, trashed entries included,
+    /// are zeroed first, and the plaintext temp file is removed.
+    pub fn lock(&mut self, why: &str) {
+        if self.vault.is_none() {
+            return;
+        }
+        let _ = self.clear_clip();
+        self.flush_uses();
+        wipe(&mut self.master_pwd);
+        wipe(&mut self.gen_pwd);
+        wipe(&mut self.input_buffer);
+        wipe(&mut self.input_buffer2);
+        let entries = self
+            .vault
+            .take()
+            .map(|v| v.e.into_iter().chain(v.trash).collect::<Vec<_>>())
+            .unwrap_or_default();
+        for mut e in entries.into_iter().chain(self.entry_disp.drain(..)) {
+            wipe_entry(&mut e);
+        }
+        wipe(&mut self.n_entry_pass);
+        wipe(&mut self.n_entry_notes);
+        self.n_entry_data.values_mut().for_each(wipe);
+        for field in &mut self.n_entry_fields {
+            wipe(&mut field.value);
+        }
+        wipe(&mut self.field_input);
+        self.ca_form();
+        self.reveal = None;
+        self.search_query.clear();
+        self.search_idx = None;
+        self.search_hits.clear();
+        self.active_tf = None;
+        self.audit.clear();
+        self.all_tags.clear();
+        self.gen_ret = None;
+        self.selected_entry = 0;
+        self.selected_menu = 0;
+        self.screen = Screen::UnlockVault;
+        self.input_field = InputField::Password;
+        match storage::rm_tmp() {
+            Ok(()) => self.set_msg(why, MessageType::Info),
+            Err(e) => self.set_msg(
+                &format!("{why}; could not remove temp file: {e}"),
+                MessageType::Error,
+            ),
+        }
+    }
+
+    /// Called on every pass of the event loop to lock an idle vault,
+    /// re-mask a timed-out reveal and clear an expired copy.
+    pub fn tick(&mut self) {
+        let mins = self.config.lock_mins();
+        if mins > 0 && self.last_input.elapsed() >= Duration::from_secs(mins * 60) {
+            self.lock(&format!("Locked after {mins} min idle"));
+        }
+        if self
+            .reveal
+            .as_ref()
+            .is_some_and(|(_, until)| Instant::now() >= *until)
+        {
+            self.reveal = None;
+        }
+        if self.clip.is_some_and(|(at, _)| Instant::now() >= at) {
+            if let Ok(true) = self.clear_clip() {
+                self.set_msg("Clipboard cleared", MessageType::Info);
             }
         }
     }
 
+    /// Clears our copy from the clipboard now, unless something else has
+    /// been copied over it.
+    pub fn clear_clip(&mut self) -> Result<bool, String> {
+        match self.clip.take() {
+            Some((_, tag)) => clipboard::clear_if(&tag),
+            None => Ok(false),
+        }
+    }
+
+    /// Switches to the next sort mode, keeping the selected entry selected,
+    /// and remembers the choice in the config file.
+    pub fn cycle_sort(&mut self) {
+        let selected = self
+            .entry_disp
+            .get(self.selected_entry)
+            .map(|e| e.id.clone());
+        self.config.sort = self.config.sort.next();
+        self.sort_disp();
+        if let Some(id) = selected {
+            self.selected_entry = self.entry_disp.iter().position(|e| e.id == id).unwrap_or(0);
+        }
+        match config::save(&self.config) {
+            Ok(()) => self.set_msg(
+                &format!("Sorted by {}", self.config.sort.label()),
+                MessageType::Info,
+            ),
+            Err(e) => self.set_msg(&format!("Failed to save config: {e}"), MessageType::Error),
+        }
+    }
+
+    /// Re-runs the security audit over the unlocked vault.
+    pub fn run_audit(&mut self) {
+        let max_age = self.config.audit_max_age.unwrap_or(DEFAULT_MAX_AGE);
+        self.audit = self.vault.as_ref().map_or(Vec::new(), |v| {
+            audit::run(v, crate::get_timestamp(), max_age)
+        });
+        self.audit_sel = self.audit_sel.min(self.audit.len().saturating_sub(1));
+    }
+
+    /// Restores the history record under the cursor of the history screen,
+    /// which lists newest first.
+    pub fn restore_history(&mut self) {
+        let Some(id) = self
+            .entry_disp
+            .get(self.selected_entry)
+            .map(|e| e.id.clone())
+        else {
+            return;
+        };
+        let Some(ref mut vault) = self.vault else {
+            return;
+        };
+        let depth = vault.history_depth;
+        let Some(entry) = vault.e.iter_mut().find(|e| e.id == id) else {
+            return;
+        };
+        let Some(idx) = entry.history.len().checked_sub(self.hist_sel + 1) else {
+            return;
+        };
+        let field = entry.history[idx].field;
+        if let Err(e) = entry.restore_history(idx, depth) {
+            self.set_msg(&e, MessageType::Error);
+            return;
+        }
+        let updated = entry.clone();
+        if let Err(e) = storage::svv(vault, &self.master_pwd) {
+            self.set_msg(&format!("Failed to save: {e}"), MessageType::Error);
+            return;
+        }
+        for e in self.entry_disp.iter_mut().filter(|e| e.id == id) {
+            *e = updated.clone();
+        }
+        self.hist_sel = 0;
+        self.set_msg(&format!("{} restored", field.label()), MessageType::Success);
+    }
+
+    /// Flips the favorite flag on the selected entry.
+    pub fn toggle_fav(&mut self) {
+        let Some(id) = self
+            .entry_disp
+            .get(self.selected_entry)
+            .map(|e| e.id.clone())
+        else {
+            return;
+        };
+        let Some(ref mut vault) = self.vault else {
+            return;
+        };
+        let Some(entry) = vault.e.iter_mut().find(|e| e.id == id) else {
+            return;
+        };
+        entry.favorite = !entry.favorite;
+        let fav = entry.favorite;
+        if let Err(e) = storage::svv(vault, &self.master_pwd) {
+            self.set_msg(&format!("Failed to save: {e}"), MessageType::Error);
+            return;
+        }
+        for e in self.entry_disp.iter_mut().filter(|e| e.id == id) {
+            e.favorite = fav;
+        }
+        self.sort_disp();
+        self.selected_entry = self.entry_disp.iter().position(|e| e.id == id).unwrap_or(0);
+        self.set_msg(
+            if fav {
+                "Added to favorites"
+            } else {
+                "Removed from favorites"
+            },
+            MessageType::Success,
+        );
+    }
+
     pub fn gen_pwd(&mut self) {
-        let len = self
-            .input_buffer
-            .parse::<usize>()
-            .unwrap_or(16)
-            .clamp(4, 64);
-        self.gen_pwd = crypto::gen_pwd(len);
+        if let Ok(len) = self.input_buffer.parse::<usize>() {
+            self.gen_policy.len = len.clamp(MIN_LEN, MAX_LEN);
+        }
+        self.input_buffer = self.gen_policy.len.to_string();
+        match crypto::gen_pwd(&self.gen_policy) {
+            Ok(pwd) => {
+                self.gen_pwd = pwd;
+                self.msg.clear();
+            }
+            Err(e) => {
+                self.gen_pwd.clear();
+                self.set_msg(&e, MessageType::Error);
+            }
+        }
+    }
+
+    pub fn gen_rows(&self) -> &'static [GenRow] {
+        match self.gen_policy.mode() {
+            Mode::Chars => GEN_CHAR_ROWS,
+            Mode::Words => GEN_WORD_ROWS,
+            Mode::Pronounceable => GEN_PRON_ROWS,
+            Mode::Pattern => GEN_PATTERN_ROWS,
+        }
+    }
+
+    pub fn gen_row(&self) -> GenRow {
+        let rows = self.gen_rows();
+        rows[self.gen_sel.min(rows.len() - 1)]
+    }
+
+    fn gen_class(&mut self) -> Option<&mut Option<usize>> {
+        match self.gen_row() {
+            GenRow::Lower => Some(&mut self.gen_policy.lower),
+            GenRow::Upper => Some(&mut self.gen_policy.upper),
+            GenRow::Digits => Some(&mut self.gen_policy.digits),
+            GenRow::Symbols => Some(&mut self.gen_policy.symbols),
+            _ => None,
+        }
+    }
+
+    /// Left/Right on a generator row: the mode, the length or word count, or
+    /// a class minimum that drops to "off" below zero.
+    pub fn adjust_gen(&mut self, up: bool) {
+        let step = |n: usize, lo: usize, hi: usize| {
+            if up { n + 1 } else { n.saturating_sub(1) }.clamp(lo, hi)
+        };
+        match self.gen_row() {
+            GenRow::Mode => {
+                let mode = self.gen_policy.mode();
+                self.gen_policy
+                    .set_mode(if up { mode.next() } else { mode.prev() });
+                self.gen_sel = 0;
+            }
+            GenRow::Length => {
+                let len = self.input_buffer.parse().unwrap_or(self.gen_policy.len);
+                self.gen_policy.len = step(len, MIN_LEN, MAX_LEN);
+                self.input_buffer = self.gen_policy.len.to_string();
+            }
+            GenRow::Words => {
+                let n = self.gen_policy.words.unwrap_or(DEFAULT_WORDS);
+                self.gen_policy.words = Some(step(n, MIN_WORDS, MAX_WORDS));
+            }
+            _ => {
+                if let Some(class) = self.gen_class() {
+                    *class = match (*class, up) {
+                        (None, true) => Some(0),
+                        (Some(n), true) => Some(n + 1),
+                        (Some(0) | None, false) => None,
+                        (Some(n), false) => Some(n - 1),
+                    };
+                } else {
+                    self.toggle_gen();
+                }
+            }
+        }
+    }
+
+    pub fn toggle_gen(&mut self) {
+        let row = self.gen_row();
+        let p = &mut self.gen_policy;
+        match row {
+            GenRow::Mode => {
+                p.set_mode(p.mode().next());
+                self.gen_sel = 0;
+            }
+            GenRow::NoAmbiguous => p.no_ambiguous = !p.no_ambiguous,
+            GenRow::NoRepeat => p.no_repeat = !p.no_repeat,
+            GenRow::Capitalize => p.capitalize = !p.capitalize,
+            GenRow::Insert => p.insert = !p.insert,
+            _ => {
+                if let Some(class) = self.gen_class() {
+                    *class = if class.is_some() { None } else { Some(1) };
+                }
+            }
+        }
+    }
+
+    /// Opens the generator on the entry's own rules from the add/edit form.
+    pub fn open_gen_for_form(&mut self) {
+        if let Some(ref policy) = self.n_entry_policy {
+            self.gen_policy = policy.clone();
+        }
+        self.gen_ret = Some(self.screen.clone());
+        self.input_buffer = self.gen_policy.len.to_string();
+        self.gen_pwd.clear();
+        self.gen_sel = 0;
+        self.msg.clear();
+        self.screen = Screen::GeneratePassword;
+    }
+
+    /// Returns to the form, saving the generator settings as the entry's
+    /// rules (and using the generated password) or clearing them.
+    pub fn close_gen_for_form(&mut self, save: bool) {
+        let Some(ret) = self.gen_ret.take() else {
+            return;
+        };
+        if save {
+            if self.gen_pwd.is_empty() {
+                self.gen_pwd();
+            }
+            if self.gen_pwd.is_empty() {
+                self.gen_ret = Some(ret);
+                return;
+            }
+            self.n_entry_pass = std::mem::take(&mut self.gen_pwd);
+            self.n_entry_policy = Some(self.gen_policy.clone());
+            self.set_msg("Password generated; site rules saved", MessageType::Success);
+        } else {
+            self.n_entry_policy = None;
+            self.set_msg("Site rules removed", MessageType::Info);
+        }
+        self.input_buffer.clear();
+        self.screen = ret;
+    }
+
+    /// Regenerates the form's password from the entry's rules, or the
+    /// generator's current settings when it has none.
+    pub fn regen_form_pwd(&mut self) {
+        let policy = self.n_entry_policy.as_ref().unwrap_or(&self.gen_policy);
+        match crypto::gen_pwd(policy) {
+            Ok(pwd) => {
+                let msg = if self.n_entry_policy.is_some() {
+                    "Generated with the site rules"
+                } else {
+                    "Generated"
+                };
+                self.n_entry_pass = pwd;
+                self.set_msg(msg, MessageType::Success);
+            }
+            Err(e) => self.set_msg(&e, MessageType::Error),
+        }
     }
 
     pub fn set_msg(&mut self, msg: &str, msg_type: MessageType) {
@@ -306,12 +982,21 @@ impl App {
 
     pub fn ca_form(&mut self) {
         self.n_entry_name.clear();
+        self.n_entry_folder.clear();
+        self.n_entry_rotate.clear();
+        self.n_entry_policy = None;
         self.n_entry_user.clear();
         self.n_entry_pass.clear();
         self.n_entry_url.clear();
         self.n_entry_notes.clear();
+        self.n_entry_data.clear();
+        self.n_entry_kind = EntryKind::Login;
         self.n_entry_tags.clear();
         self.tag_input.clear();
+        self.n_entry_fields.clear();
+        self.field_input.clear();
+        self.field_kind = FieldKind::Text;
+        self.field_sel = None;
         self.add_fi = 0;
         self.edit_eid.clear();
     }
@@ -330,16 +1015,202 @@ impl App {
         }
     }
 
+    /// Adds a custom field typed as `name=value` in the form, or replaces
+    /// the selected one.
+    pub fn add_field(&mut self) {
+        let Some((name, value)) = self.field_input.split_once('=') else {
+            self.set_msg(
+                "Custom fields are entered as name=value",
+                MessageType::Error,
+            );
+            return;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            self.set_msg("Custom field name is required", MessageType::Error);
+            return;
+        }
+        let field = CustomField {
+            name: name.to_string(),
+            value: value.trim().to_string(),
+            kind: self.field_kind.clone(),
+        };
+        match self.field_sel.take() {
+            Some(i) if i < self.n_entry_fields.len() => {
+                self.n_entry_fields[i] = field;
+                self.set_msg("Field updated", MessageType::Success);
+            }
+            _ => {
+                self.n_entry_fields.push(field);
+                self.msg.clear();
+            }
+        }
+        self.field_input.clear();
+        self.field_kind = FieldKind::Text;
+    }
+
+    /// Moves the field selection by `delta`, wrapping through the "new
+    /// field" input past either end, and loads the selected field into the
+    /// input for editing.
+    pub fn select_field(&mut self, delta: isize) {
+        let n = self.n_entry_fields.len() as isize;
+        if n == 0 {
+            return;
+        }
+        // Position n stands for the new-field input.
+        let cur = self.field_sel.map_or(n, |i| i as isize);
+        let next = (cur + delta).rem_euclid(n + 1);
+        self.field_input.clear();
+        if next == n {
+            self.field_sel = None;
+            self.field_kind = FieldKind::Text;
+        } else {
+            let field = &self.n_entry_fields[next as usize];
+            self.field_input = format!("{}={}", field.name, field.value);
+            self.field_kind = field.kind.clone();
+            self.field_sel = Some(next as usize);
+        }
+    }
+
+    /// Removes the selected custom field.
+    pub fn delete_field(&mut self) {
+        let Some(i) = self.field_sel.take() else {
+            self.set_msg("Select a field with ↑/↓ to delete it", MessageType::Info);
+            return;
+        };
+        if i < self.n_entry_fields.len() {
+            let field = self.n_entry_fields.remove(i);
+            self.set_msg(
+                &format!("Field '{}' removed", field.name),
+                MessageType::Success,
+            );
+        }
+        self.field_input.clear();
+        self.field_kind = FieldKind::Text;
+    }
+
+    /// Moves the selected custom field one place earlier or later.
+    pub fn move_field(&mut self, up: bool) {
+        let Some(i) = self.field_sel else {
+            return;
+        };
+        let j = if up { i.checked_sub(1) } else { Some(i + 1) };
+        if let Some(j) = j.filter(|&j| j < self.n_entry_fields.len()) {
+            self.n_entry_fields.swap(i, j);
+            self.field_sel = Some(j);
+        }
+    }
+
     pub fn load_at(&mut self) {
         if let Some(ref vault) = self.vault {
-            let mut tag_map: HashMap<String, usize> = HashMap::new();
-            for entry in &vault.e {
-                for tag in &entry.tags {
-                    *tag_map.entry(tag.clone()).or_insert(0) += 1;
+            self.all_tags = vault.tag_counts();
+        }
+    }
+
+    /// Entries a pending tag operation would touch, from the `all_tags`
+    /// counts. A merge only needs a scan when several tags may overlap.
+    pub fn tag_preview(&self, op: &TagOp) -> usize {
+        let count = |tag: &str| {
+            self.all_tags
+                .iter()
+                .find(|(t, _)| t == tag)
+                .map_or(0, |(_, n)| *n)
+        };
+        match op {
+            TagOp::Rename(tag) | TagOp::Delete(tag) | TagOp::Interval(tag) => count(tag),
+            TagOp::Merge(tags) if tags.len() == 1 => count(&tags[0]),
+            TagOp::Merge(tags) => self.vault.as_ref().map_or(0, |v| {
+                v.e.iter()
+                    .filter(|e| e.tags.iter().any(|t| tags.contains(t)))
+                    .count()
+            }),
+        }
+    }
+
+    /// Runs the pending tag operation as a single save.
+    pub fn apply_tag_op(&mut self) {
+        let Some(op) = self.tag_op.take() else {
+            return;
+        };
+        let target = self.input_buffer.trim().to_lowercase();
+        self.input_buffer.clear();
+        if let TagOp::Interval(tag) = op {
+            self.set_tag_interval(&tag, &target);
+            return;
+        }
+        let exists = self.all_tags.iter().any(|(t, _)| *t == target);
+        let (from, to, done) = match op {
+            TagOp::Rename(tag) => {
+                if target.is_empty() || target == tag {
+                    return;
+                }
+                if exists {
+                    self.set_msg(
+                        &format!("Tag '{target}' already exists, use merge instead"),
+                        MessageType::Error,
+                    );
+                    return;
                 }
+                let done = format!("Renamed '{tag}' to '{target}'");
+                (vec![tag], Some(target), done)
             }
-            self.all_tags = tag_map.into_iter().collect();
-            self.all_tags.sort_by(|a, b| b.1.cmp(&a.1));
+            TagOp::Merge(tags) => {
+                if target.is_empty() {
+                    self.set_msg("Merge target is required", MessageType::Error);
+                    return;
+                }
+                let done = format!("Merged {} tag(s) into '{target}'", tags.len());
+                (tags, Some(target), done)
+            }
+            TagOp::Delete(tag) => {
+                let done = format!("Deleted tag '{tag}'");
+                (vec![tag], None, done)
+            }
+            TagOp::Interval(_) => return,
+        };
+        if let Some(ref mut vault) = self.vault {
+            let changed = vault.retag(&from, to.as_deref());
+            if let Err(e) = storage::svv(vault, &self.master_pwd) {
+                self.set_msg(&format!("Failed to save: {e}"), MessageType::Error);
+                return;
+            }
+            self.entry_disp = vault.e.clone();
+            self.sort_disp();
+            self.tag_marked.clear();
+            self.load_at();
+            self.tag_sel = self.tag_sel.min(self.all_tags.len().saturating_sub(1));
+            self.set_msg(&format!("{done} ({changed} entries)"), MessageType::Success);
+        }
+    }
+
+    /// Sets (or with an empty/zero value clears) the rotation interval for
+    /// every entry carrying `tag`.
+    fn set_tag_interval(&mut self, tag: &str, days: &str) {
+        let days = if days.is_empty() {
+            0
+        } else if let Ok(d) = days.parse::<u32>() {
+            d
+        } else {
+            self.set_msg("Interval must be a number of days", MessageType::Error);
+            return;
+        };
+        let Some(ref mut vault) = self.vault else {
+            return;
+        };
+        if days == 0 {
+            vault.tag_rotation.remove(tag);
+        } else {
+            vault.tag_rotation.insert(tag.to_string(), days);
+        }
+        if let Err(e) = storage::svv(vault, &self.master_pwd) {
+            self.set_msg(&format!("Failed to save: {e}"), MessageType::Error);
+        } else if days == 0 {
+            self.set_msg(&format!("'{tag}' no longer rotates"), MessageType::Success);
+        } else {
+            self.set_msg(
+                &format!("'{tag}' rotates every {days} days"),
+                MessageType::Success,
+            );
         }
     }
 
@@ -357,6 +1228,118 @@ impl App {
                 self.entry_disp = vault.e.clone();
             }
         }
+        self.sort_disp();
+    }
+
+    /// Visible rows of the folder tree: sub-folders first, then entries,
+    /// descending only into expanded folders. Unfiled entries come last.
+    pub fn tree_rows(&self) -> Vec<TreeRow> {
+        let Some(ref vault) = self.vault else {
+            return Vec::new();
+        };
+        let folders = vault.folders();
+        let mut rows = Vec::new();
+        self.push_tree(vault, &folders, None, 0, &mut rows);
+        rows
+    }
+
+    fn push_tree(
+        &self,
+        vault: &Vault,
+        folders: &BTreeSet<String>,
+        parent: Option<&str>,
+        depth: usize,
+        rows: &mut Vec<TreeRow>,
+    ) {
+        let children = folders.iter().filter(|f| match parent {
+            Some(p) => f
+                .strip_prefix(p)
+                .and_then(|r| r.strip_prefix('/'))
+                .is_some_and(|r| !r.contains('/')),
+            None => !f.contains('/'),
+        });
+        for folder in children {
+            rows.push(TreeRow::Folder {
+                path: folder.clone(),
+                depth,
+            });
+            if self.folder_open.contains(folder) {
+                self.push_tree(vault, folders, Some(folder), depth + 1, rows);
+            }
+        }
+        let mut entries: Vec<&Entry> = vault
+            .e
+            .iter()
+            .filter(|e| e.folder.as_deref() == parent)
+            .collect();
+        entries.sort_by_key(|e| e.n.to_lowercase());
+        rows.extend(entries.into_iter().map(|e| TreeRow::Entry {
+            id: e.id.clone(),
+            depth,
+        }));
+    }
+
+    /// Applies the pending rename/move typed into `input_buffer`.
+    pub fn apply_folder_op(&mut self) {
+        let Some(op) = self.folder_op.take() else {
+            return;
+        };
+        let input = self.input_buffer.trim().to_string();
+        self.input_buffer.clear();
+        let Some(mut vault) = self.vault.take() else {
+            return;
+        };
+        let parent_of = |path: &str| path.rsplit_once('/').map(|(p, _)| p.to_string());
+        let leaf_of = |path: &str| path.rsplit('/').next().unwrap_or(path).to_string();
+        let result = match op {
+            FolderOp::Rename(ref path) => {
+                if input.is_empty() || input.contains('/') {
+                    Err("Folder names cannot be empty or contain '/'".to_string())
+                } else {
+                    let to = match parent_of(path) {
+                        Some(p) => format!("{p}/{input}"),
+                        None => input.clone(),
+                    };
+                    vault.move_folder(path, &to).map(|n| (to, n))
+                }
+            }
+            FolderOp::Move(ref path) => {
+                let to = match norm_folder(&input) {
+                    Some(p) => format!("{p}/{}", leaf_of(path)),
+                    None => leaf_of(path),
+                };
+                vault.move_folder(path, &to).map(|n| (to, n))
+            }
+            FolderOp::MoveEntry(ref id) => match vault.e.iter_mut().find(|e| &e.id == id) {
+                Some(e) => {
+                    e.folder = norm_folder(&input);
+                    e.last_modified = crate::get_timestamp();
+                    Ok((input.clone(), 1))
+                }
+                None => Err("Entry not found!".to_string()),
+            },
+        };
+        let saved = result.and_then(|r| storage::svv(&vault, &self.master_pwd).map(|()| r));
+        if saved.is_ok() {
+            self.entry_disp = vault.e.clone();
+        }
+        self.vault = Some(vault);
+        self.sort_disp();
+        match saved {
+            Ok((to, n)) => {
+                if let Some(to) = norm_folder(&to) {
+                    self.folder_open.insert(to);
+                }
+                self.set_msg(
+                    &format!(
+                        "Moved {n} entries to '{}'",
+                        if to.is_empty() { "/" } else { &to }
+                    ),
+                    MessageType::Success,
+                );
+            }
+            Err(e) => self.set_msg(&e, MessageType::Error),
+        }
     }
 
     pub fn get_ta(timestamp: u64) -> String {
@@ -386,3 +1369,24 @@ impl App {
         }
     }
 }
+
+/// Zeroes a string's buffer before it is freed.
+fn wipe(s: &mut String) {
+    let mut bytes = std::mem::take(s).into_bytes();
+    crypto::secure_wipe(&mut bytes);
+}
+
+/// Zeroes everything in an entry that may be secret: the password, notes,
+/// typed data, custom fields, the OTP seed and history.
+fn wipe_entry(e: &mut Entry) {
+    wipe(&mut e.p);
+    e.nt.iter_mut().for_each(wipe);
+    e.otp.iter_mut().for_each(wipe);
+    e.data.values_mut().for_each(wipe);
+    for field in &mut e.fields {
+        wipe(&mut field.value);
+    }
+    for h in &mut e.history {
+        wipe(&mut h.password);
+    }
+}
@@ -1,5 +1,9 @@
 use super::app::App;
-use super::screens::{InputField, MessageType, Screen};
+use super::screens::{
+    FolderOp, FormSlot, GenRow, InputField, MessageType, Screen, TagOp, TrashOp, TreeRow, MENU,
+    MENU_LEFT,
+};
+use crate::search;
 use crossterm::event::KeyCode;
 
 pub fn handle_cvi(app: &mut App, key: KeyCode) {
@@ -35,7 +39,8 @@ pub fn handle_cvi(app: &mut App, key: KeyCode) {
     }
 }
 
-pub fn handle_uvi(app: &mut App, key: KeyCode) {
+/// Returns true when the user asked to quit.
+pub fn handle_uvi(app: &mut App, key: KeyCode) -> bool {
     match key {
         KeyCode::Char(c) => {
             app.input_buffer.push(c);
@@ -46,168 +51,162 @@ pub fn handle_uvi(app: &mut App, key: KeyCode) {
         KeyCode::Enter => {
             app.unlock_vault();
         }
-        KeyCode::Esc => {
-            std::process::exit(0);
-        }
+        KeyCode::Esc => return true,
         _ => {}
     }
+    false
 }
 
-#[allow(clippy::too_many_lines)]
 pub fn handle_mmi(app: &mut App, key: KeyCode) -> bool {
     match key {
-        KeyCode::Up => {
-            if app.selected_menu > 0 {
-                app.selected_menu -= 1;
-                if app.selected_menu < 3 {
-                    app.selected_section = 0;
-                } else {
-                    app.selected_section = 1;
-                }
-            }
+        KeyCode::Up if app.selected_menu > 0 => {
+            app.selected_menu -= 1;
         }
-        KeyCode::Down => {
-            if app.selected_menu < 6 {
-                app.selected_menu += 1;
-                if app.selected_menu < 3 {
-                    app.selected_section = 0;
-                } else {
-                    app.selected_section = 1;
-                }
-            }
+        KeyCode::Down if app.selected_menu < MENU.len() - 1 => {
+            app.selected_menu += 1;
         }
-        KeyCode::Left => {
-            app.selected_section = 0;
-            if app.selected_menu > 2 {
-                app.selected_menu = 0;
-            }
+        KeyCode::Left if app.selected_menu >= MENU_LEFT => {
+            app.selected_menu = 0;
+        }
+        KeyCode::Right if app.selected_menu < MENU_LEFT => {
+            app.selected_menu = MENU_LEFT;
         }
-        KeyCode::Right => {
-            app.selected_section = 1;
-            if app.selected_menu < 3 {
-                app.selected_menu = 3;
+        KeyCode::Char(c) => {
+            if let Some(item) = MENU.iter().position(|m| m.0.eq_ignore_ascii_case(&c)) {
+                app.msg.clear();
+                return open_menu(app, item);
             }
         }
-        KeyCode::Char('1') => {
+        KeyCode::Esc => return true,
+        KeyCode::Enter => {
+            app.msg.clear();
+            return open_menu(app, app.selected_menu);
+        }
+        _ => {}
+    }
+    app.selected_section = usize::from(app.selected_menu >= MENU_LEFT);
+    false
+}
+
+/// Opens main menu item `item`. Returns true for Exit.
+fn open_menu(app: &mut App, item: usize) -> bool {
+    match MENU[item].0 {
+        '1' => {
             app.screen = Screen::ViewPasswords;
             app.msg.clear();
             app.active_tf = None;
             app.search_query.clear();
-            if let Some(ref vault) = app.vault {
-                app.entry_disp = vault.e.clone();
-            }
+            app.show_all();
         }
-        KeyCode::Char('2') => {
+        '2' => {
             app.screen = Screen::AddPassword;
             app.ca_form();
-            app.msg.clear();
         }
-        KeyCode::Char('3') => {
+        '3' => {
             app.screen = Screen::SearchPassword;
             app.search_query.clear();
             app.entry_disp.clear();
+            app.search_hits.clear();
+            app.search_idx = app.vault.as_ref().map(|v| search::Index::new(&v.e));
+            app.selected_entry = 0;
             app.msg.clear();
         }
-        KeyCode::Char('4') => {
+        't' => {
+            app.screen = Screen::ManageTags;
+            app.load_at();
+            app.tag_sel = 0;
+            app.tag_marked.clear();
+            app.tag_op = None;
+        }
+        'r' => {
+            app.screen = Screen::Due;
+            app.due_sel = 0;
+        }
+        'a' => {
+            app.screen = Screen::Audit;
+            app.audit_sel = 0;
+            app.run_audit();
+        }
+        'x' => {
+            app.screen = Screen::Trash;
+            app.trash_sel = 0;
+            app.trash_op = None;
+        }
+        'p' => app.toggle_present(),
+        'f' => {
+            app.screen = Screen::Folders;
+            app.folder_sel = 0;
+            app.folder_op = None;
+        }
+        '4' => {
             app.screen = Screen::FilterByTag;
             app.select_tf = 0;
             app.filter_bt(None);
-            app.msg.clear();
         }
-        KeyCode::Char('5') => {
+        '5' => {
             app.screen = Screen::GeneratePassword;
-            app.input_buffer = String::from("16");
+            app.input_buffer = app.gen_policy.len.to_string();
             app.gen_pwd.clear();
-            app.msg.clear();
+            app.gen_sel = 0;
+            app.gen_ret = None;
         }
-        KeyCode::Char('6') => {
+        '6' => {
             app.screen = Screen::DeletePassword;
             app.input_buffer.clear();
-            app.msg.clear();
+            app.del_confirm = None;
+            app.selected_entry = 0;
             if app.entry_disp.is_empty() {
-                if let Some(ref vault) = app.vault {
-                    app.entry_disp = vault.e.clone();
-                }
-            }
-        }
-        KeyCode::Char('7') | KeyCode::Esc => return true,
-        KeyCode::Enter => {
-            app.msg.clear();
-            match app.selected_menu {
-                0 => {
-                    app.screen = Screen::ViewPasswords;
-                    app.active_tf = None;
-                    app.search_query.clear();
-                    if let Some(ref vault) = app.vault {
-                        app.entry_disp = vault.e.clone();
-                    }
-                }
-                1 => {
-                    app.screen = Screen::AddPassword;
-                    app.ca_form();
-                }
-                2 => {
-                    app.screen = Screen::SearchPassword;
-                    app.search_query.clear();
-                    app.entry_disp.clear();
-                }
-                3 => {
-                    app.screen = Screen::FilterByTag;
-                    app.select_tf = 0;
-                    app.filter_bt(None);
-                }
-                4 => {
-                    app.screen = Screen::GeneratePassword;
-                    app.input_buffer = String::from("16");
-                    app.gen_pwd.clear();
-                }
-                5 => {
-                    app.screen = Screen::DeletePassword;
-                    app.input_buffer.clear();
-                    if app.entry_disp.is_empty() {
-                        if let Some(ref vault) = app.vault {
-                            app.entry_disp = vault.e.clone();
-                        }
-                    }
-                }
-                6 => return true,
-                _ => {}
+                app.show_all();
             }
         }
+        '7' => return true,
         _ => {}
     }
     false
 }
 
+/// Moves the selection of an entry list: a line, a page or to either end.
+/// Returns false for any other key.
+fn nav_entries(app: &mut App, key: KeyCode) -> bool {
+    let last = app.entry_disp.len().saturating_sub(1);
+    let sel = app.selected_entry;
+    app.selected_entry = match key {
+        KeyCode::Up => sel.saturating_sub(1),
+        KeyCode::Down => (sel + 1).min(last),
+        KeyCode::PageUp => sel.saturating_sub(app.page_len),
+        KeyCode::PageDown => (sel + app.page_len).min(last),
+        KeyCode::Home => 0,
+        KeyCode::End => last,
+        _ => return false,
+    };
+    true
+}
+
 pub fn handle_vpi(app: &mut App, key: KeyCode) {
+    if nav_entries(app, key) {
+        return;
+    }
     match key {
-        KeyCode::Up => {
-            if app.selected_entry > 0 {
-                app.selected_entry -= 1;
-            }
-        }
-        KeyCode::Down => {
-            if app.selected_entry < app.entry_disp.len().saturating_sub(1) {
-                app.selected_entry += 1;
-            }
-        }
-        KeyCode::Char('e' | 'E') => {
-            if app.selected_entry < app.entry_disp.len() {
-                let entry_id = app.entry_disp[app.selected_entry].id.clone();
-                app.load_efe(&entry_id);
-            }
-        }
-        KeyCode::Char('h' | 'H') => {
-            if app.selected_entry < app.entry_disp.len() {
-                app.screen = Screen::ViewHistory;
-            }
+        KeyCode::Char('e' | 'E') if app.selected_entry < app.entry_disp.len() => {
+            let entry_id = app.entry_disp[app.selected_entry].id.clone();
+            app.load_efe(&entry_id);
         }
+        KeyCode::Char('h' | 'H') if app.selected_entry < app.entry_disp.len() => {
+            app.hist_sel = 0;
+            app.msg.clear();
+            app.screen = Screen::ViewHistory;
+        }
+        KeyCode::Char('s' | 'S') => app.cycle_sort(),
+        KeyCode::Char('*') => app.toggle_fav(),
+        KeyCode::Char('c' | 'C') => app.copy_sel(false),
+        KeyCode::Char('u' | 'U') => app.copy_sel(true),
+        KeyCode::Char('v' | 'V') => app.reveal_sel(),
+        KeyCode::Char('p' | 'P') => app.toggle_present(),
+        KeyCode::Char('l' | 'L') => app.toggle_compact(),
         KeyCode::Char('f' | 'F') => {
             app.active_tf = None;
             app.search_query.clear();
-            if let Some(ref vault) = app.vault {
-                app.entry_disp = vault.e.clone();
-            }
+            app.show_all();
             app.selected_entry = 0;
             app.set_msg("Filters cleared", MessageType::Success);
         }
@@ -219,14 +218,22 @@ pub fn handle_vpi(app: &mut App, key: KeyCode) {
     }
 }
 
-pub fn handle_api(app: &mut App, key: KeyCode) {
+/// Key handling shared by the add and edit forms. Returns true when the
+/// user asked to save.
+fn handle_form(app: &mut App, key: KeyCode) -> bool {
+    let slot = app.cur_slot();
     match key {
-        KeyCode::Char(c) => match app.add_fi {
-            0 => app.n_entry_name.push(c),
-            1 => app.n_entry_user.push(c),
-            2 => app.n_entry_pass.push(c),
-            3 => app.n_entry_url.push(c),
-            4 => {
+        KeyCode::Char(c) => match slot {
+            FormSlot::Kind => {}
+            FormSlot::Name => app.n_entry_name.push(c),
+            FormSlot::Folder => app.n_entry_folder.push(c),
+            FormSlot::Rotate => {
+                if c.is_ascii_digit() {
+                    app.n_entry_rotate.push(c);
+                }
+            }
+            FormSlot::Field(sf) => app.form_val_mut(sf.key).push(c),
+            FormSlot::Tags => {
                 if !c.is_ascii_digit() {
                     app.tag_input.push(c);
                 } else if let Some(digit) = c.to_digit(10) {
@@ -234,134 +241,123 @@ pub fn handle_api(app: &mut App, key: KeyCode) {
                     app.remove_tag(idx);
                 }
             }
-            5 => app.n_entry_notes.push(c),
-            _ => {}
+            FormSlot::Fields => app.field_input.push(c),
         },
-        KeyCode::Backspace => match app.add_fi {
-            0 => {
+        KeyCode::Backspace => match slot {
+            FormSlot::Kind => {}
+            FormSlot::Name => {
                 app.n_entry_name.pop();
             }
-            1 => {
-                app.n_entry_user.pop();
+            FormSlot::Folder => {
+                app.n_entry_folder.pop();
             }
-            2 => {
-                app.n_entry_pass.pop();
+            FormSlot::Rotate => {
+                app.n_entry_rotate.pop();
             }
-            3 => {
-                app.n_entry_url.pop();
+            FormSlot::Field(sf) => {
+                app.form_val_mut(sf.key).pop();
             }
-            4 => {
+            FormSlot::Tags => {
                 if app.tag_input.is_empty() && !app.n_entry_tags.is_empty() {
                     app.n_entry_tags.pop();
                 } else {
                     app.tag_input.pop();
                 }
             }
-            5 => {
-                app.n_entry_notes.pop();
+            FormSlot::Fields => {
+                app.field_input.pop();
             }
-            _ => {}
         },
+        KeyCode::Up if slot == FormSlot::Fields => app.select_field(-1),
+        KeyCode::Down if slot == FormSlot::Fields => app.select_field(1),
+        KeyCode::PageUp if slot == FormSlot::Fields => app.move_field(true),
+        KeyCode::PageDown if slot == FormSlot::Fields => app.move_field(false),
+        KeyCode::Delete if slot == FormSlot::Fields => app.delete_field(),
         KeyCode::Tab => {
-            app.add_fi = (app.add_fi + 1) % 6;
-        }
-        KeyCode::Enter => {
-            if app.add_fi == 4 {
-                app.add_tag();
-            } else if app.add_fi == 5 {
-                app.n_entry_notes.push('\n');
-            } else {
-                app.add_entry();
-            }
-        }
-        KeyCode::Esc => {
-            app.screen = Screen::MainMenu;
-            app.ca_form();
+            app.add_fi = (app.add_fi + 1) % app.form_slots().len();
         }
-        _ => {}
-    }
-}
-
-pub fn handle_epi(app: &mut App, key: KeyCode) {
-    match key {
-        KeyCode::Char(c) => match app.add_fi {
-            0 => app.n_entry_name.push(c),
-            1 => app.n_entry_user.push(c),
-            2 => app.n_entry_pass.push(c),
-            3 => app.n_entry_url.push(c),
-            4 => {
-                if !c.is_ascii_digit() {
-                    app.tag_input.push(c);
-                } else if let Some(digit) = c.to_digit(10) {
-                    let idx = (digit as usize).saturating_sub(1);
-                    app.remove_tag(idx);
-                }
-            }
-            5 => app.n_entry_notes.push(c),
+        KeyCode::Left => match slot {
+            FormSlot::Kind => app.n_entry_kind = app.n_entry_kind.prev(),
+            FormSlot::Fields => app.field_kind = app.field_kind.prev(),
             _ => {}
         },
-        KeyCode::Backspace => match app.add_fi {
-            0 => {
-                app.n_entry_name.pop();
-            }
-            1 => {
-                app.n_entry_user.pop();
-            }
-            2 => {
-                app.n_entry_pass.pop();
-            }
-            3 => {
-                app.n_entry_url.pop();
-            }
-            4 => {
-                if app.tag_input.is_empty() && !app.n_entry_tags.is_empty() {
-                    app.n_entry_tags.pop();
-                } else {
-                    app.tag_input.pop();
-                }
-            }
-            5 => {
-                app.n_entry_notes.pop();
-            }
+        KeyCode::Right => match slot {
+            FormSlot::Kind => app.n_entry_kind = app.n_entry_kind.next(),
+            FormSlot::Fields => app.field_kind = app.field_kind.next(),
             _ => {}
         },
-        KeyCode::Tab => {
-            app.add_fi = (app.add_fi + 1) % 6;
-        }
-        KeyCode::Enter => {
-            if app.add_fi == 4 {
-                app.add_tag();
-            } else if app.add_fi == 5 {
-                app.n_entry_notes.push('\n');
-            } else {
-                app.edit_entry();
-            }
+        KeyCode::Enter => match slot {
+            FormSlot::Tags => app.add_tag(),
+            FormSlot::Fields => app.add_field(),
+            FormSlot::Field(sf) if sf.multiline => app.form_val_mut(sf.key).push('\n'),
+            _ => return true,
+        },
+        KeyCode::F(2 | 3) if !app.n_entry_kind.schema().iter().any(|f| f.key == "p") => {
+            app.set_msg("This entry type has no password", MessageType::Error);
         }
+        KeyCode::F(2) => app.regen_form_pwd(),
+        KeyCode::F(3) => app.open_gen_for_form(),
+        KeyCode::F(4) => app.toggle_reveal(&app.edit_eid.clone()),
         KeyCode::Esc => {
             app.screen = Screen::MainMenu;
             app.ca_form();
         }
         _ => {}
     }
+    false
+}
+
+pub fn handle_api(app: &mut App, key: KeyCode) {
+    if handle_form(app, key) {
+        app.add_entry();
+    }
+}
+
+pub fn handle_epi(app: &mut App, key: KeyCode) {
+    if handle_form(app, key) {
+        app.edit_entry();
+    }
 }
 
 pub fn handle_vhi(app: &mut App, key: KeyCode) {
-    if key == KeyCode::Esc {
-        app.screen = Screen::ViewPasswords;
+    let count = app
+        .entry_disp
+        .get(app.selected_entry)
+        .map_or(0, |e| e.history.len());
+    match key {
+        KeyCode::Up if app.hist_sel > 0 => app.hist_sel -= 1,
+        KeyCode::Down if app.hist_sel + 1 < count => app.hist_sel += 1,
+        KeyCode::Char('r' | 'R') => app.restore_history(),
+        KeyCode::Char('v' | 'V') => app.reveal_sel(),
+        KeyCode::Esc => {
+            app.msg.clear();
+            app.screen = Screen::ViewPasswords;
+        }
+        _ => {}
     }
 }
 
 pub fn handle_si(app: &mut App, key: KeyCode) {
+    if nav_entries(app, key) {
+        return;
+    }
     match key {
         KeyCode::Char(c) => {
             app.search_query.push(c);
             app.search_entries();
+            app.selected_entry = 0;
         }
         KeyCode::Backspace => {
             app.search_query.pop();
             app.search_entries();
+            app.selected_entry = 0;
         }
+        // Letters go to the query here, so copying uses function keys.
+        KeyCode::F(5) => app.copy_sel(false),
+        KeyCode::F(6) => app.copy_sel(true),
+        KeyCode::F(4) => app.reveal_sel(),
         KeyCode::Enter => {
+            app.msg.clear();
             app.screen = Screen::ViewPasswords;
         }
         KeyCode::Esc => {
@@ -372,42 +368,97 @@ pub fn handle_si(app: &mut App, key: KeyCode) {
 }
 
 pub fn handle_gi(app: &mut App, key: KeyCode) {
+    let row = app.gen_row();
     match key {
-        KeyCode::Char(c) if c.is_ascii_digit() => {
+        KeyCode::Up if app.gen_sel > 0 => app.gen_sel -= 1,
+        KeyCode::Down if app.gen_sel + 1 < app.gen_rows().len() => app.gen_sel += 1,
+        KeyCode::Left => app.adjust_gen(false),
+        KeyCode::Right => app.adjust_gen(true),
+        KeyCode::Char(c) if row == GenRow::Separator => app.gen_policy.separator.push(c),
+        KeyCode::Char(c) if row == GenRow::Pattern => {
+            app.gen_policy
+                .pattern
+                .get_or_insert_with(String::new)
+                .push(c);
+        }
+        KeyCode::Char(' ') => app.toggle_gen(),
+        KeyCode::Char(c) if row == GenRow::Length && c.is_ascii_digit() => {
             app.input_buffer.push(c);
         }
-        KeyCode::Backspace => {
+        KeyCode::Char(c) if row == GenRow::SymbolSet && !c.is_alphanumeric() => {
+            app.gen_policy.symbol_set.push(c);
+        }
+        KeyCode::Backspace if row == GenRow::Length => {
             app.input_buffer.pop();
         }
+        KeyCode::Backspace if row == GenRow::SymbolSet => {
+            app.gen_policy.symbol_set.pop();
+        }
+        KeyCode::Backspace if row == GenRow::Separator => {
+            app.gen_policy.separator.pop();
+        }
+        KeyCode::Backspace if row == GenRow::Pattern => {
+            app.gen_policy.pattern.get_or_insert_with(String::new).pop();
+        }
         KeyCode::Enter => {
             app.gen_pwd();
         }
+        KeyCode::Tab if app.gen_ret.is_some() => app.close_gen_for_form(true),
+        KeyCode::Delete if app.gen_ret.is_some() => app.close_gen_for_form(false),
         KeyCode::Esc => {
-            app.screen = Screen::MainMenu;
+            app.msg.clear();
+            app.input_buffer.clear();
+            app.screen = app.gen_ret.take().unwrap_or(Screen::MainMenu);
         }
         _ => {}
     }
 }
 
 pub fn handle_di(app: &mut App, key: KeyCode) {
+    if let Some(idx) = app.del_confirm.take() {
+        app.input_buffer.clear();
+        if !matches!(key, KeyCode::Char('y' | 'Y')) {
+            return;
+        }
+        let Some(entry_id) = app.entry_disp.get(idx).map(|e| e.id.clone()) else {
+            return;
+        };
+        if let Some(ref vault) = app.vault {
+            if let Some(vault_idx) = vault.e.iter().position(|e| e.id == entry_id) {
+                app.delete_entry(vault_idx);
+            } else {
+                app.set_msg("Entry not found in vault!", MessageType::Error);
+            }
+        }
+        return;
+    }
+    if nav_entries(app, key) {
+        app.input_buffer.clear();
+        return;
+    }
     match key {
         KeyCode::Char(c) if c.is_ascii_digit() => {
             app.input_buffer.push(c);
+            // Scroll to the entry being typed.
+            if let Ok(n @ 1..) = app.input_buffer.parse::<usize>() {
+                app.selected_entry = (n - 1).min(app.entry_disp.len().saturating_sub(1));
+            }
         }
         KeyCode::Backspace => {
             app.input_buffer.pop();
         }
         KeyCode::Enter => {
-            if let Ok(idx) = app.input_buffer.parse::<usize>() {
+            let typed = if app.input_buffer.is_empty() {
+                Ok(app.selected_entry + 1)
+            } else {
+                app.input_buffer.parse::<usize>()
+            };
+            if let Ok(idx) = typed {
                 if idx > 0 && idx <= app.entry_disp.len() {
-                    let entry_id = app.entry_disp[idx - 1].id.clone();
-                    if let Some(ref vault) = app.vault {
-                        if let Some(vault_idx) = vault.e.iter().position(|e| e.id == entry_id) {
-                            app.delete_entry(vault_idx);
-                        } else {
-                            app.set_msg("Entry not found in vault!", MessageType::Error);
-                        }
-                    }
+                    // Always ask: a stray Enter right after opening the
+                    // screen would otherwise trash the first entry.
+                    app.selected_entry = idx - 1;
+                    app.del_confirm = Some(idx - 1);
                 } else {
                     app.set_msg("Invalid entry number!", MessageType::Error);
                 }
@@ -424,15 +475,11 @@ pub fn handle_di(app: &mut App, key: KeyCode) {
 
 pub fn handle_tfi(app: &mut App, key: KeyCode) {
     match key {
-        KeyCode::Up => {
-            if app.select_tf > 0 {
-                app.select_tf -= 1;
-            }
+        KeyCode::Up if app.select_tf > 0 => {
+            app.select_tf -= 1;
         }
-        KeyCode::Down => {
-            if app.select_tf < app.all_tags.len() {
-                app.select_tf += 1;
-            }
+        KeyCode::Down if app.select_tf < app.all_tags.len() => {
+            app.select_tf += 1;
         }
         KeyCode::Enter => {
             if app.select_tf == 0 {
@@ -444,12 +491,268 @@ pub fn handle_tfi(app: &mut App, key: KeyCode) {
                 app.set_msg(&format!("Filtered by tag: {tag}"), MessageType::Success);
             }
         }
-        KeyCode::Char('v' | 'V') => {
-            if !app.entry_disp.is_empty() {
-                app.selected_entry = 0;
-                app.screen = Screen::ViewPasswords;
+        KeyCode::Char('v' | 'V') if !app.entry_disp.is_empty() => {
+            app.selected_entry = 0;
+            app.screen = Screen::ViewPasswords;
+        }
+        KeyCode::Esc => {
+            app.screen = Screen::MainMenu;
+        }
+        _ => {}
+    }
+}
+
+pub fn handle_foi(app: &mut App, key: KeyCode) {
+    if app.folder_op.is_some() {
+        match key {
+            KeyCode::Char(c) => app.input_buffer.push(c),
+            KeyCode::Backspace => {
+                app.input_buffer.pop();
+            }
+            KeyCode::Enter => app.apply_folder_op(),
+            KeyCode::Esc => {
+                app.folder_op = None;
+                app.input_buffer.clear();
+            }
+            _ => {}
+        }
+        return;
+    }
+    let rows = app.tree_rows();
+    let current = rows.get(app.folder_sel).cloned();
+    match key {
+        KeyCode::Up if app.folder_sel > 0 => {
+            app.folder_sel -= 1;
+        }
+        KeyCode::Down if app.folder_sel < rows.len().saturating_sub(1) => {
+            app.folder_sel += 1;
+        }
+        KeyCode::Right | KeyCode::Enter => match current {
+            Some(TreeRow::Folder { path, .. }) if !app.folder_open.remove(&path) => {
+                app.folder_open.insert(path);
+            }
+            Some(TreeRow::Entry { id, .. }) if key == KeyCode::Enter => app.load_efe(&id),
+            _ => {}
+        },
+        KeyCode::Left => match current {
+            Some(TreeRow::Folder { ref path, .. }) if app.folder_open.contains(path) => {
+                app.folder_open.remove(path);
+            }
+            // Jump to the enclosing folder row.
+            Some(TreeRow::Folder { depth, .. } | TreeRow::Entry { depth, .. }) if depth > 0 => {
+                if let Some(i) = rows[..app.folder_sel]
+                    .iter()
+                    .rposition(|r| matches!(r, TreeRow::Folder { depth: d, .. } if *d + 1 == depth))
+                {
+                    app.folder_sel = i;
+                }
+            }
+            _ => {}
+        },
+        KeyCode::Char('r' | 'R') => {
+            if let Some(TreeRow::Folder { path, .. }) = current {
+                app.input_buffer = path.rsplit('/').next().unwrap_or_default().to_string();
+                app.folder_op = Some(FolderOp::Rename(path));
+            }
+        }
+        KeyCode::Char('m' | 'M') => match current {
+            Some(TreeRow::Folder { path, .. }) => {
+                app.input_buffer = path
+                    .rsplit_once('/')
+                    .map(|(p, _)| p.to_string())
+                    .unwrap_or_default();
+                app.folder_op = Some(FolderOp::Move(path));
+            }
+            Some(TreeRow::Entry { id, .. }) => {
+                app.input_buffer = app
+                    .vault
+                    .as_ref()
+                    .and_then(|v| v.e.iter().find(|e| e.id == id))
+                    .and_then(|e| e.folder.clone())
+                    .unwrap_or_default();
+                app.folder_op = Some(FolderOp::MoveEntry(id));
+            }
+            None => {}
+        },
+        KeyCode::Esc => {
+            app.screen = Screen::MainMenu;
+        }
+        _ => {}
+    }
+}
+
+pub fn handle_tmi(app: &mut App, key: KeyCode) {
+    match app.tag_op {
+        Some(TagOp::Delete(_)) => {
+            if matches!(key, KeyCode::Char('y' | 'Y')) {
+                app.apply_tag_op();
+            } else {
+                app.tag_op = None;
+            }
+            return;
+        }
+        Some(_) => {
+            match key {
+                KeyCode::Char(c) => app.input_buffer.push(c),
+                KeyCode::Backspace => {
+                    app.input_buffer.pop();
+                }
+                KeyCode::Enter => app.apply_tag_op(),
+                KeyCode::Esc => {
+                    app.tag_op = None;
+                    app.input_buffer.clear();
+                }
+                _ => {}
+            }
+            return;
+        }
+        None => {}
+    }
+    let current = app.all_tags.get(app.tag_sel).map(|(t, _)| t.clone());
+    match key {
+        KeyCode::Up if app.tag_sel > 0 => {
+            app.tag_sel -= 1;
+        }
+        KeyCode::Down if app.tag_sel < app.all_tags.len().saturating_sub(1) => {
+            app.tag_sel += 1;
+        }
+        KeyCode::Char(' ') => {
+            if let Some(tag) = current {
+                if let Some(i) = app.tag_marked.iter().position(|t| *t == tag) {
+                    app.tag_marked.remove(i);
+                } else {
+                    app.tag_marked.push(tag);
+                }
+            }
+        }
+        KeyCode::Char('r' | 'R') => {
+            if let Some(tag) = current {
+                app.input_buffer.clone_from(&tag);
+                app.tag_op = Some(TagOp::Rename(tag));
+            }
+        }
+        KeyCode::Char('m' | 'M') => {
+            if app.tag_marked.is_empty() {
+                app.set_msg("Mark tags to merge with Space first", MessageType::Info);
+            } else {
+                app.input_buffer.clear();
+                app.tag_op = Some(TagOp::Merge(app.tag_marked.clone()));
+            }
+        }
+        KeyCode::Char('d' | 'D') => {
+            if let Some(tag) = current {
+                app.tag_op = Some(TagOp::Delete(tag));
+            }
+        }
+        KeyCode::Char('i' | 'I') => {
+            if let Some(tag) = current {
+                app.input_buffer = app
+                    .vault
+                    .as_ref()
+                    .and_then(|v| v.tag_rotation.get(&tag))
+                    .map_or(String::new(), u32::to_string);
+                app.tag_op = Some(TagOp::Interval(tag));
+            }
+        }
+        KeyCode::Esc => {
+            app.tag_marked.clear();
+            app.screen = Screen::MainMenu;
+        }
+        _ => {}
+    }
+}
+
+pub fn handle_dui(app: &mut App, key: KeyCode) {
+    let due: Vec<String> = app.vault.as_ref().map_or(Vec::new(), |v| {
+        v.due(crate::get_timestamp())
+            .into_iter()
+            .map(|(e, _)| e.id.clone())
+            .collect()
+    });
+    match key {
+        KeyCode::Up if app.due_sel > 0 => {
+            app.due_sel -= 1;
+        }
+        KeyCode::Down if app.due_sel < due.len().saturating_sub(1) => {
+            app.due_sel += 1;
+        }
+        KeyCode::Enter | KeyCode::Char('e' | 'E') => {
+            if let Some(id) = due.get(app.due_sel) {
+                app.load_efe(id);
+            }
+        }
+        KeyCode::Esc => {
+            app.screen = Screen::MainMenu;
+        }
+        _ => {}
+    }
+}
+
+pub fn handle_tri(app: &mut App, key: KeyCode) {
+    match app.trash_op {
+        Some(TrashOp::Purge) => {
+            if matches!(key, KeyCode::Char('y' | 'Y')) {
+                app.apply_trash(true);
+            }
+            app.trash_op = None;
+            return;
+        }
+        Some(TrashOp::Days) => {
+            match key {
+                KeyCode::Char(c) if c.is_ascii_digit() => app.input_buffer.push(c),
+                KeyCode::Backspace => {
+                    app.input_buffer.pop();
+                }
+                KeyCode::Enter => app.set_trash_days(),
+                KeyCode::Esc => {
+                    app.trash_op = None;
+                    app.input_buffer.clear();
+                }
+                _ => {}
+            }
+            return;
+        }
+        None => {}
+    }
+    let count = app.vault.as_ref().map_or(0, |v| v.trash.len());
+    match key {
+        KeyCode::Up if app.trash_sel > 0 => {
+            app.trash_sel -= 1;
+        }
+        KeyCode::Down if app.trash_sel < count.saturating_sub(1) => {
+            app.trash_sel += 1;
+        }
+        KeyCode::Enter | KeyCode::Char('r' | 'R') if count > 0 => app.apply_trash(false),
+        KeyCode::Char('p' | 'P') if count > 0 => app.trash_op = Some(TrashOp::Purge),
+        KeyCode::Char('a' | 'A') => {
+            app.input_buffer = app
+                .vault
+                .as_ref()
+                .map_or(String::new(), |v| v.trash_days.to_string());
+            app.trash_op = Some(TrashOp::Days);
+        }
+        KeyCode::Esc => {
+            app.msg.clear();
+            app.screen = Screen::MainMenu;
+        }
+        _ => {}
+    }
+}
+
+pub fn handle_aui(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Up if app.audit_sel > 0 => {
+            app.audit_sel -= 1;
+        }
+        KeyCode::Down if app.audit_sel < app.audit.len().saturating_sub(1) => {
+            app.audit_sel += 1;
+        }
+        KeyCode::Enter | KeyCode::Char('e' | 'E') => {
+            if let Some(id) = app.audit.get(app.audit_sel).map(|f| f.id.clone()) {
+                app.load_efe(&id);
             }
         }
+        KeyCode::Char('r' | 'R') => app.run_audit(),
         KeyCode::Esc => {
             app.screen = Screen::MainMenu;
         }
@@ -5,38 +5,51 @@ pub mod screens;
 pub mod widgets;
 
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
+    event::{
+        self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+        Event, KeyCode, KeyEventKind, KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Frame, Terminal};
 use std::io;
+use std::time::{Duration, Instant};
 
 use app::App;
 use handlers::{
-    handle_api, handle_cvi, handle_di, handle_epi, handle_gi, handle_mmi, handle_si, handle_tfi,
-    handle_uvi, handle_vhi, handle_vpi,
+    handle_api, handle_aui, handle_cvi, handle_di, handle_dui, handle_epi, handle_foi, handle_gi,
+    handle_mmi, handle_si, handle_tfi, handle_tmi, handle_tri, handle_uvi, handle_vhi, handle_vpi,
 };
 use screens::Screen;
 use widgets::{
-    draw_add_pwd, draw_create_vault, draw_del_pwd, draw_edit_pwd, draw_filter_tags, draw_gen_pwd,
-    draw_history, draw_loading, draw_main_menu, draw_search_pwd, draw_unlock_vault, draw_view_pwds,
+    draw_add_pwd, draw_audit, draw_create_vault, draw_del_pwd, draw_due, draw_edit_pwd,
+    draw_filter_tags, draw_folders, draw_gen_pwd, draw_history, draw_loading, draw_main_menu,
+    draw_manage_tags, draw_search_pwd, draw_trash, draw_unlock_vault, draw_view_pwds,
 };
 
 pub fn run_tui() -> Result<(), Box<dyn std::error::Error>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     let mut app = App::new();
     app.check_vault();
     let res = run_app(&mut terminal, &mut app);
+    // Exiting locks too, so nothing is left on the clipboard or on disk.
+    app.lock("");
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableFocusChange
     )?;
     terminal.show_cursor()?;
     if let Err(err) = res {
@@ -50,13 +63,30 @@ fn run_app<B: ratatui::backend::Backend>(
     app: &mut App,
 ) -> io::Result<()> {
     loop {
+        app.tick();
         terminal.draw(|f| ui(f, app))?;
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
+        // Wake up once a second so time-based views (TOTP countdown) redraw.
+        if !event::poll(Duration::from_secs(1))? {
+            continue;
+        }
+        match event::read()? {
+            Event::FocusLost if app.config.lock_on_blur() => {
+                app.lock("Locked: terminal lost focus")
+            }
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                app.last_input = Instant::now();
+                if key.code == KeyCode::Char('l') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                    app.lock("Locked");
+                    continue;
+                }
                 match app.screen {
                     Screen::VaultCheck => {}
                     Screen::CreateVault => handle_cvi(app, key.code),
-                    Screen::UnlockVault => handle_uvi(app, key.code),
+                    Screen::UnlockVault => {
+                        if handle_uvi(app, key.code) {
+                            return Ok(());
+                        }
+                    }
                     Screen::MainMenu => {
                         if handle_mmi(app, key.code) {
                             return Ok(());
@@ -70,13 +100,19 @@ fn run_app<B: ratatui::backend::Backend>(
                     Screen::GeneratePassword => handle_gi(app, key.code),
                     Screen::DeletePassword => handle_di(app, key.code),
                     Screen::FilterByTag => handle_tfi(app, key.code),
+                    Screen::Folders => handle_foi(app, key.code),
+                    Screen::ManageTags => handle_tmi(app, key.code),
+                    Screen::Due => handle_dui(app, key.code),
+                    Screen::Trash => handle_tri(app, key.code),
+                    Screen::Audit => handle_aui(app, key.code),
                 }
             }
+            _ => {}
         }
     }
 }
 
-fn ui(f: &mut Frame, app: &App) {
+fn ui(f: &mut Frame, app: &mut App) {
     let size = f.size();
     match app.screen {
         Screen::VaultCheck => draw_loading(f, size),
@@ -91,5 +127,10 @@ fn ui(f: &mut Frame, app: &App) {
         Screen::GeneratePassword => draw_gen_pwd(f, size, app),
         Screen::DeletePassword => draw_del_pwd(f, size, app),
         Screen::FilterByTag => draw_filter_tags(f, size, app),
+        Screen::Folders => draw_folders(f, size, app),
+        Screen::ManageTags => draw_manage_tags(f, size, app),
+        Screen::Due => draw_due(f, size, app),
+        Screen::Trash => draw_trash(f, size, app),
+        Screen::Audit => draw_audit(f, size, app),
     }
 }
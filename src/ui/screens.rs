@@ -1,3 +1,5 @@
+use crate::models::SchemaField;
+
 #[derive(Clone, PartialEq)]
 pub enum Screen {
     VaultCheck,
@@ -12,6 +14,118 @@ pub enum Screen {
     GeneratePassword,
     DeletePassword,
     FilterByTag,
+    Folders,
+    ManageTags,
+    Due,
+    Trash,
+    Audit,
+}
+
+/// Main menu items as (hotkey, title, description). The first `MENU_LEFT`
+/// form the PASSWORDS panel, the rest the TOOLS panel.
+pub const MENU: &[(char, &str, &str)] = &[
+    ('1', "View All", "Browse vault"),
+    ('2', "Add New", "Create entry"),
+    ('3', "Search", "Find passwords"),
+    ('f', "Folders", "Browse folder tree"),
+    ('4', "Filter Tags", "Sort by tags"),
+    ('t', "Manage Tags", "Rename, merge, delete"),
+    ('r', "Rotation", "Expiring passwords"),
+    ('a', "Audit", "Security report"),
+    ('5', "Generate", "Random password"),
+    ('6', "Delete", "Move to trash"),
+    ('x', "Trash", "Restore or purge"),
+    ('p', "Presentation", "Hide all secrets"),
+    ('7', "Exit", "Lock & quit"),
+];
+pub const MENU_LEFT: usize = 5;
+
+/// One option row of the generator screen.
+#[derive(Clone, Copy, PartialEq)]
+pub enum GenRow {
+    Mode,
+    Length,
+    Lower,
+    Upper,
+    Digits,
+    Symbols,
+    SymbolSet,
+    NoAmbiguous,
+    NoRepeat,
+    Words,
+    Separator,
+    Capitalize,
+    Insert,
+    Pattern,
+}
+
+impl GenRow {
+    pub fn label(self) -> &'static str {
+        match self {
+            GenRow::Mode => "Mode",
+            GenRow::Length => "Length",
+            GenRow::Lower => "Lowercase",
+            GenRow::Upper => "Uppercase",
+            GenRow::Digits => "Digits",
+            GenRow::Symbols => "Symbols",
+            GenRow::SymbolSet => "Symbol set",
+            GenRow::NoAmbiguous => "Exclude ambiguous",
+            GenRow::NoRepeat => "No repeats",
+            GenRow::Words => "Words",
+            GenRow::Separator => "Separator",
+            GenRow::Capitalize => "Capitalize",
+            GenRow::Insert => "Add digit/symbol",
+            GenRow::Pattern => "Pattern",
+        }
+    }
+}
+
+pub const GEN_CHAR_ROWS: &[GenRow] = &[
+    GenRow::Mode,
+    GenRow::Length,
+    GenRow::Lower,
+    GenRow::Upper,
+    GenRow::Digits,
+    GenRow::Symbols,
+    GenRow::SymbolSet,
+    GenRow::NoAmbiguous,
+    GenRow::NoRepeat,
+];
+pub const GEN_WORD_ROWS: &[GenRow] = &[
+    GenRow::Mode,
+    GenRow::Words,
+    GenRow::Separator,
+    GenRow::Capitalize,
+    GenRow::Insert,
+];
+pub const GEN_PRON_ROWS: &[GenRow] = &[GenRow::Mode, GenRow::Length];
+pub const GEN_PATTERN_ROWS: &[GenRow] = &[GenRow::Mode, GenRow::Pattern];
+
+/// One visible row of the folder tree.
+#[derive(Clone, PartialEq)]
+pub enum TreeRow {
+    Folder { path: String, depth: usize },
+    Entry { id: String, depth: usize },
+}
+
+/// Pending folder-tree edit whose new value is typed into `input_buffer`.
+#[derive(Clone, PartialEq)]
+pub enum FolderOp {
+    Rename(String),
+    Move(String),
+    MoveEntry(String),
+}
+
+/// One focusable row of the add/edit entry form.
+#[derive(Clone, Copy, PartialEq)]
+pub enum FormSlot {
+    Kind,
+    Name,
+    Folder,
+    Rotate,
+    Field(&'static SchemaField),
+    Tags,
+    Fields,
 }
 
 #[derive(Clone, PartialEq)]
@@ -28,3 +142,19 @@ pub enum MessageType {
     Error,
     Info,
 }
+
+/// Pending trash-screen operation.
+#[derive(Clone, PartialEq)]
+pub enum TrashOp {
+    Purge,
+    Days,
+}
+
+/// Pending tag-manager operation; new names are typed into `input_buffer`.
+#[derive(Clone, PartialEq)]
+pub enum TagOp {
+    Rename(String),
+    Merge(Vec<String>),
+    Delete(String),
+    Interval(String),
+}
@@ -0,0 +1,113 @@
+use super::super::app::App;
+use super::super::colors::GruvboxColors;
+use crate::audit::Kind;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+fn kind_color(kind: Kind) -> Color {
+    match kind {
+        Kind::Breached | Kind::Reused | Kind::Weak => GruvboxColors::red(),
+        Kind::Old | Kind::Insecure => GruvboxColors::orange(),
+        Kind::Duplicate => GruvboxColors::yellow(),
+        Kind::MissingUser | Kind::MissingUrl => GruvboxColors::blue(),
+    }
+}
+
+pub fn draw_audit(f: &mut Frame, size: Rect, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(3),
+        ])
+        .split(size);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(GruvboxColors::red()))
+        .title("═══ SECURITY AUDIT ═══")
+        .title_alignment(Alignment::Center)
+        .style(Style::default().bg(GruvboxColors::bg0()));
+    f.render_widget(block, size);
+
+    let mut summary: Vec<Span> = Vec::new();
+    let mut last = None;
+    for finding in &app.audit {
+        if last == Some(finding.kind) {
+            continue;
+        }
+        last = Some(finding.kind);
+        let n = app.audit.iter().filter(|g| g.kind == finding.kind).count();
+        if !summary.is_empty() {
+            summary.push(Span::styled(
+                " │ ",
+                Style::default().fg(GruvboxColors::gray()),
+            ));
+        }
+        summary.push(Span::styled(
+            format!("{n} {}", finding.kind.label().to_lowercase()),
+            Style::default().fg(kind_color(finding.kind)),
+        ));
+    }
+    if summary.is_empty() {
+        summary.push(Span::styled(
+            "No problems found",
+            Style::default().fg(GruvboxColors::green()),
+        ));
+    }
+    let title = Paragraph::new(Line::from(summary)).alignment(Alignment::Center);
+    f.render_widget(title, chunks[0]);
+
+    if app.audit.is_empty() {
+        let empty = Paragraph::new("[ Every entry passed ]")
+            .style(Style::default().fg(GruvboxColors::gray()))
+            .alignment(Alignment::Center);
+        f.render_widget(empty, chunks[1]);
+    } else {
+        let items: Vec<ListItem> = app
+            .audit
+            .iter()
+            .enumerate()
+            .map(|(i, finding)| {
+                let is_selected = i == app.audit_sel;
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        if is_selected { "▶ " } else { "  " },
+                        Style::default().fg(GruvboxColors::yellow()),
+                    ),
+                    Span::styled(
+                        format!("[{}] ", finding.kind.label()),
+                        Style::default().fg(kind_color(finding.kind)),
+                    ),
+                    Span::styled(
+                        finding.path.as_str(),
+                        if is_selected {
+                            Style::default()
+                                .fg(GruvboxColors::yellow())
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(GruvboxColors::fg())
+                        },
+                    ),
+                    Span::styled(
+                        format!("  {}", finding.detail),
+                        Style::default().fg(GruvboxColors::gray()),
+                    ),
+                ]))
+            })
+            .collect();
+        let list = List::new(items).block(Block::default().borders(Borders::NONE));
+        let mut state = ListState::default().with_selected(Some(app.audit_sel));
+        f.render_stateful_widget(list, chunks[1], &mut state);
+    }
+    let help = Paragraph::new("↑/↓: Navigate │ Enter: Edit entry │ R: Re-run │ Esc: Back")
+        .style(Style::default().fg(GruvboxColors::gray()))
+        .alignment(Alignment::Center);
+    f.render_widget(help, chunks[2]);
+}
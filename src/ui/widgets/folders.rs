@@ -0,0 +1,140 @@
+use super::super::app::App;
+use super::super::colors::GruvboxColors;
+use super::super::screens::{FolderOp, MessageType, TreeRow};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+pub fn draw_folders(f: &mut Frame, size: Rect, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(2),
+            Constraint::Length(3),
+        ])
+        .split(size);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(GruvboxColors::aqua()))
+        .title("═══ FOLDERS ═══")
+        .title_alignment(Alignment::Center)
+        .style(Style::default().bg(GruvboxColors::bg0()));
+    f.render_widget(block, size);
+
+    let folder_count = app.vault.as_ref().map_or(0, |v| v.folders().len());
+    let title = Paragraph::new(format!("{folder_count} folders"))
+        .style(Style::default().fg(GruvboxColors::yellow()))
+        .alignment(Alignment::Center);
+    f.render_widget(title, chunks[0]);
+
+    let rows = app.tree_rows();
+    if rows.is_empty() {
+        let empty = Paragraph::new("[ No passwords saved yet ]")
+            .style(Style::default().fg(GruvboxColors::gray()))
+            .alignment(Alignment::Center);
+        f.render_widget(empty, chunks[1]);
+    } else {
+        let items: Vec<ListItem> = rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let is_selected = i == app.folder_sel;
+                let prefix = if is_selected { "▶ " } else { "  " };
+                let mut spans = vec![Span::styled(
+                    prefix,
+                    Style::default().fg(GruvboxColors::yellow()),
+                )];
+                match row {
+                    TreeRow::Folder { path, depth } => {
+                        let open = app.folder_open.contains(path);
+                        let name = path.rsplit('/').next().unwrap_or(path);
+                        let count = app.vault.as_ref().map_or(0, |v| {
+                            v.e.iter()
+                                .filter(|e| {
+                                    e.folder.as_deref().is_some_and(|f| {
+                                        f == path || f.starts_with(&format!("{path}/"))
+                                    })
+                                })
+                                .count()
+                        });
+                        spans.push(Span::raw("  ".repeat(*depth)));
+                        spans.push(Span::styled(
+                            format!("{} {name}/", if open { "▾" } else { "▸" }),
+                            Style::default()
+                                .fg(GruvboxColors::aqua())
+                                .add_modifier(Modifier::BOLD),
+                        ));
+                        spans.push(Span::styled(
+                            format!("  ({count})"),
+                            Style::default().fg(GruvboxColors::gray()),
+                        ));
+                    }
+                    TreeRow::Entry { id, depth } => {
+                        let name = app
+                            .vault
+                            .as_ref()
+                            .and_then(|v| v.e.iter().find(|e| &e.id == id))
+                            .map_or("", |e| e.n.as_str());
+                        spans.push(Span::raw("  ".repeat(*depth)));
+                        spans.push(Span::styled(
+                            format!("  {name}"),
+                            if is_selected {
+                                Style::default()
+                                    .fg(GruvboxColors::yellow())
+                                    .add_modifier(Modifier::BOLD)
+                            } else {
+                                Style::default().fg(GruvboxColors::fg())
+                            },
+                        ));
+                    }
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items).block(Block::default().borders(Borders::NONE));
+        let mut state = ListState::default().with_selected(Some(app.folder_sel));
+        f.render_stateful_widget(list, chunks[1], &mut state);
+    }
+
+    if let Some(ref op) = app.folder_op {
+        let label = match op {
+            FolderOp::Rename(path) => format!("Rename '{path}' to"),
+            FolderOp::Move(path) => format!("Move '{path}' into (empty = top level)"),
+            FolderOp::MoveEntry(_) => "Move entry to folder (empty = none)".to_string(),
+        };
+        let input = Paragraph::new(format!("{label}: {}", app.input_buffer)).style(
+            Style::default()
+                .fg(GruvboxColors::green())
+                .add_modifier(Modifier::BOLD),
+        );
+        f.render_widget(input, chunks[2]);
+    } else if !app.msg.is_empty() {
+        let msg_style = match app.msg_type {
+            MessageType::Success => Style::default().fg(GruvboxColors::green()),
+            MessageType::Error => Style::default().fg(GruvboxColors::red()),
+            MessageType::Info => Style::default().fg(GruvboxColors::blue()),
+            MessageType::None => Style::default().fg(GruvboxColors::fg()),
+        };
+        let msg = Paragraph::new(app.msg.as_str())
+            .style(msg_style)
+            .alignment(Alignment::Center);
+        f.render_widget(msg, chunks[2]);
+    }
+
+    let help_text = if app.folder_op.is_some() {
+        "Enter: Apply │ Esc: Cancel"
+    } else {
+        "↑/↓: Navigate │ Enter/→: Open/Edit │ ←: Collapse │ R: Rename │ M: Move │ Esc: Back"
+    };
+    let help = Paragraph::new(help_text)
+        .style(Style::default().fg(GruvboxColors::gray()))
+        .alignment(Alignment::Center);
+    f.render_widget(help, chunks[3]);
+}
@@ -1,6 +1,6 @@
 use super::super::app::App;
 use super::super::colors::GruvboxColors;
-use super::super::screens::MessageType;
+use super::super::screens::{MessageType, MENU, MENU_LEFT};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
@@ -9,7 +9,40 @@ use ratatui::{
     Frame,
 };
 
-#[allow(clippy::too_many_lines)]
+/// Renders one menu panel; `offset` is the index of its first item in `MENU`.
+fn menu_items<'a>(app: &App, offset: usize, items: &'a [(char, &str, &str)]) -> Vec<ListItem<'a>> {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, (key, title, desc))| {
+            let is_selected = app.selected_menu == offset + i;
+            let style = if is_selected {
+                Style::default()
+                    .fg(GruvboxColors::yellow())
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(GruvboxColors::fg())
+            };
+            let prefix = if is_selected { "▶ " } else { "  " };
+            let lines = vec![
+                Line::from(vec![
+                    Span::styled(prefix, Style::default().fg(GruvboxColors::yellow())),
+                    Span::styled(
+                        format!("[{}] ", key.to_ascii_uppercase()),
+                        Style::default().fg(GruvboxColors::orange()),
+                    ),
+                    Span::styled(*title, style),
+                ]),
+                Line::from(vec![
+                    Span::raw("     "),
+                    Span::styled(*desc, Style::default().fg(GruvboxColors::gray())),
+                ]),
+            ];
+            ListItem::new(lines)
+        })
+        .collect()
+}
+
 pub fn draw_main_menu(f: &mut Frame, size: Rect, app: &App) {
     let main_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -61,6 +94,16 @@ pub fn draw_main_menu(f: &mut Frame, size: Rect, app: &App) {
                     format!("{tag_count} tags"),
                     Style::default().fg(GruvboxColors::purple()),
                 ),
+                Span::styled(
+                    if app.config.present {
+                        " │ PRESENTATION"
+                    } else {
+                        ""
+                    },
+                    Style::default()
+                        .fg(GruvboxColors::orange())
+                        .add_modifier(Modifier::BOLD),
+                ),
             ]),
         ]
     } else {
@@ -93,45 +136,7 @@ pub fn draw_main_menu(f: &mut Frame, size: Rect, app: &App) {
         .title_alignment(Alignment::Center)
         .style(Style::default().bg(GruvboxColors::bg0()));
 
-    let left_items = [
-        ("1", "View All", "Browse vault"),
-        ("2", "Add New", "Create entry"),
-        ("3", "Search", "Find passwords"),
-    ];
-
-    let left_list: Vec<ListItem> = left_items
-        .iter()
-        .enumerate()
-        .map(|(i, (num, title, desc))| {
-            let is_selected = app.selected_section == 0 && i == app.selected_menu;
-            let style = if is_selected {
-                Style::default()
-                    .fg(GruvboxColors::yellow())
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(GruvboxColors::fg())
-            };
-            let prefix = if is_selected { "▶ " } else { "  " };
-            let lines = vec![
-                Line::from(vec![
-                    Span::styled(prefix, Style::default().fg(GruvboxColors::yellow())),
-                    Span::styled(
-                        format!("[{num}] "),
-                        Style::default().fg(GruvboxColors::orange()),
-                    ),
-                    Span::styled(*title, style),
-                ]),
-                Line::from(vec![
-                    Span::raw("     "),
-                    Span::styled(*desc, Style::default().fg(GruvboxColors::gray())),
-                ]),
-                Line::from(""),
-            ];
-            ListItem::new(lines)
-        })
-        .collect();
-
-    let left = List::new(left_list).block(left_block);
+    let left = List::new(menu_items(app, 0, &MENU[..MENU_LEFT])).block(left_block);
     f.render_widget(left, content_layout[0]);
 
     // Right panel - Tools
@@ -142,46 +147,7 @@ pub fn draw_main_menu(f: &mut Frame, size: Rect, app: &App) {
         .title_alignment(Alignment::Center)
         .style(Style::default().bg(GruvboxColors::bg0()));
 
-    let right_items = [
-        ("4", "Filter Tags", "Sort by tags"),
-        ("5", "Generate", "Random password"),
-        ("6", "Delete", "Remove entry"),
-        ("7", "Exit", "Lock & quit"),
-    ];
-
-    let right_list: Vec<ListItem> = right_items
-        .iter()
-        .enumerate()
-        .map(|(i, (num, title, desc))| {
-            let is_selected = app.selected_section == 1 && i == app.selected_menu - 3;
-            let style = if is_selected {
-                Style::default()
-                    .fg(GruvboxColors::yellow())
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(GruvboxColors::fg())
-            };
-            let prefix = if is_selected { "▶ " } else { "  " };
-            let lines = vec![
-                Line::from(vec![
-                    Span::styled(prefix, Style::default().fg(GruvboxColors::yellow())),
-                    Span::styled(
-                        format!("[{num}] "),
-                        Style::default().fg(GruvboxColors::orange()),
-                    ),
-                    Span::styled(*title, style),
-                ]),
-                Line::from(vec![
-                    Span::raw("     "),
-                    Span::styled(*desc, Style::default().fg(GruvboxColors::gray())),
-                ]),
-                Line::from(""),
-            ];
-            ListItem::new(lines)
-        })
-        .collect();
-
-    let right = List::new(right_list).block(right_block);
+    let right = List::new(menu_items(app, MENU_LEFT, &MENU[MENU_LEFT..])).block(right_block);
     f.render_widget(right, content_layout[1]);
 
     // Message area
@@ -208,10 +174,11 @@ pub fn draw_main_menu(f: &mut Frame, size: Rect, app: &App) {
         .borders(Borders::ALL)
         .border_style(Style::default().fg(GruvboxColors::gray()))
         .style(Style::default().bg(GruvboxColors::bg0()));
-    let help =
-        Paragraph::new("↑/↓: Navigate  │  ←/→: Switch section  │  Enter: Select  │  Esc: Exit")
-            .block(help_block)
-            .style(Style::default().fg(GruvboxColors::gray()))
-            .alignment(Alignment::Center);
+    let help = Paragraph::new(
+        "↑/↓: Navigate  │  ←/→: Switch section  │  Enter: Select  │  Ctrl+L: Lock  │  Esc: Exit",
+    )
+    .block(help_block)
+    .style(Style::default().fg(GruvboxColors::gray()))
+    .alignment(Alignment::Center);
     f.render_widget(help, main_layout[2]);
 }
@@ -1,9 +1,20 @@
+pub mod audit;
+pub mod folders;
 pub mod menu;
 pub mod passwords;
+pub mod rotation;
+pub mod trash;
 pub mod utility;
 pub mod vault;
 
+/// Stands in for a hidden secret. Fixed width so the length does not leak.
+pub const MASK: &str = "••••••••";
+
+pub use audit::draw_audit;
+pub use folders::draw_folders;
 pub use menu::draw_main_menu;
 pub use passwords::{draw_add_pwd, draw_del_pwd, draw_edit_pwd, draw_history, draw_view_pwds};
-pub use utility::{draw_filter_tags, draw_gen_pwd, draw_search_pwd};
+pub use rotation::draw_due;
+pub use trash::draw_trash;
+pub use utility::{draw_filter_tags, draw_gen_pwd, draw_manage_tags, draw_search_pwd};
 pub use vault::{draw_create_vault, draw_loading, draw_unlock_vault};
@@ -1,18 +1,47 @@
 use super::super::app::App;
 use super::super::colors::GruvboxColors;
-use super::super::screens::MessageType;
-use super::utility::centered_rect;
+use super::super::screens::{FormSlot, MessageType};
+use super::utility::{centered_rect, render_entries};
+use super::MASK;
+use crate::attachments;
 use crate::crypto;
+use crate::models::{EntryKind, HistField};
+use crate::totp::{OtpKind, OtpParams};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 
+/// Current one-time code, split in half for readability, with the seconds
+/// left before it rolls over.
+fn otp_span(otp: Result<OtpParams, String>) -> Span<'static> {
+    match otp {
+        Ok(params) => {
+            let (code, remaining) = params.now();
+            let (a, b) = code.split_at(code.len() / 2);
+            let suffix = match (remaining, &params.kind) {
+                (Some(secs), _) => format!("  ({secs}s)"),
+                (None, OtpKind::Hotp { counter }) => format!("  (HOTP #{counter})"),
+                (None, OtpKind::Totp { .. }) => String::new(),
+            };
+            let color = match remaining {
+                Some(secs) if secs <= 5 => GruvboxColors::red(),
+                _ => GruvboxColors::orange(),
+            };
+            Span::styled(format!("{a} {b}{suffix}"), Style::default().fg(color))
+        }
+        Err(e) => Span::styled(
+            format!("invalid ({e})"),
+            Style::default().fg(GruvboxColors::red()),
+        ),
+    }
+}
+
 #[allow(clippy::too_many_lines)]
-pub fn draw_view_pwds(f: &mut Frame, size: Rect, app: &App) {
+pub fn draw_view_pwds(f: &mut Frame, size: Rect, app: &mut App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
@@ -37,9 +66,15 @@ pub fn draw_view_pwds(f: &mut Frame, size: Rect, app: &App) {
         String::new()
     };
     let title = Paragraph::new(format!(
-        "Total: {} entries{} | Press E to edit, H for history",
+        "Total: {} entries{} | Sorted by {} | Press E to edit, H for history{}",
         app.entry_disp.len(),
-        filter_status
+        filter_status,
+        app.config.sort.label(),
+        if app.config.present {
+            " | PRESENTATION MODE"
+        } else {
+            ""
+        }
     ))
     .style(Style::default().fg(GruvboxColors::yellow()))
     .alignment(Alignment::Center);
@@ -63,44 +98,183 @@ pub fn draw_view_pwds(f: &mut Frame, size: Rect, app: &App) {
                 let is_selected = i == app.selected_entry;
                 let prefix = if is_selected { "▶ " } else { "  " };
                 let time_ago = App::get_ta(entry.last_modified);
-                let mut lines = vec![
-                    Line::from(vec![
-                        Span::styled(prefix, Style::default().fg(GruvboxColors::yellow())),
-                        Span::styled(
-                            format!("[{}] ", i + 1),
-                            Style::default().fg(GruvboxColors::orange()),
-                        ),
-                        Span::styled(
-                            &entry.n,
-                            if is_selected {
-                                Style::default()
-                                    .fg(GruvboxColors::yellow())
-                                    .add_modifier(Modifier::BOLD)
-                            } else {
-                                Style::default().fg(GruvboxColors::yellow())
-                            },
-                        ),
-                        Span::styled(
-                            format!("  (Modified: {time_ago})"),
-                            Style::default().fg(GruvboxColors::gray()),
-                        ),
-                    ]),
-                    Line::from(vec![
+                let shown = app.shown(&entry.id);
+                let mask = |value: &str| {
+                    if shown || value.is_empty() {
+                        value.to_string()
+                    } else {
+                        MASK.to_string()
+                    }
+                };
+                let mut head = vec![
+                    Span::styled(prefix, Style::default().fg(GruvboxColors::yellow())),
+                    Span::styled(
+                        format!("[{}] ", i + 1),
+                        Style::default().fg(GruvboxColors::orange()),
+                    ),
+                    Span::styled(
+                        if entry.favorite { "★ " } else { "" },
+                        Style::default().fg(GruvboxColors::yellow()),
+                    ),
+                    Span::styled(
+                        &entry.n,
+                        if is_selected {
+                            Style::default()
+                                .fg(GruvboxColors::yellow())
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(GruvboxColors::yellow())
+                        },
+                    ),
+                    Span::styled(
+                        entry
+                            .folder
+                            .as_ref()
+                            .map_or(String::new(), |folder| format!("  in {folder}/")),
+                        Style::default().fg(GruvboxColors::aqua()),
+                    ),
+                    Span::styled(
+                        if entry.kind == EntryKind::Login {
+                            String::new()
+                        } else {
+                            format!("  <{}>", entry.kind.label())
+                        },
+                        Style::default().fg(GruvboxColors::purple()),
+                    ),
+                    Span::styled(
+                        if entry.attachments.is_empty() {
+                            String::new()
+                        } else {
+                            format!("  [{} file(s)]", entry.attachments.len())
+                        },
+                        Style::default().fg(GruvboxColors::aqua()),
+                    ),
+                    Span::styled(
+                        match app
+                            .vault
+                            .as_ref()
+                            .and_then(|v| v.days_left(entry, crate::get_timestamp()))
+                        {
+                            Some(d) if d < 0 => "  [rotation overdue]",
+                            _ => "",
+                        },
+                        Style::default().fg(GruvboxColors::red()),
+                    ),
+                    Span::styled(
+                        match entry.breached {
+                            Some(0) => "  [breached]".to_string(),
+                            Some(n) => format!("  [breached ×{n}]"),
+                            None => String::new(),
+                        },
+                        Style::default()
+                            .fg(GruvboxColors::red())
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!("  (Modified: {time_ago})"),
+                        Style::default().fg(GruvboxColors::gray()),
+                    ),
+                ];
+                if app.config.compact {
+                    if !entry.u.is_empty() {
+                        head.insert(
+                            4,
+                            Span::styled(
+                                format!("  {}", entry.u),
+                                Style::default().fg(GruvboxColors::blue()),
+                            ),
+                        );
+                    }
+                    return ListItem::new(Line::from(head));
+                }
+                let mut lines = vec![Line::from(head)];
+                if entry.kind == EntryKind::Login {
+                    lines.push(Line::from(vec![
                         Span::raw("     "),
                         Span::styled("├─ User: ", Style::default().fg(GruvboxColors::gray())),
                         Span::styled(&entry.u, Style::default().fg(GruvboxColors::blue())),
-                    ]),
-                    Line::from(vec![
+                    ]));
+                    lines.push(Line::from(vec![
                         Span::raw("     "),
                         Span::styled("├─ Pass: ", Style::default().fg(GruvboxColors::gray())),
-                        Span::styled(&entry.p, Style::default().fg(GruvboxColors::green())),
-                    ]),
-                ];
-                if let Some(ref url) = entry.url {
+                        Span::styled(mask(&entry.p), Style::default().fg(GruvboxColors::green())),
+                    ]));
+                    if let Some(ref url) = entry.url {
+                        lines.push(Line::from(vec![
+                            Span::raw("     "),
+                            Span::styled("├─ URL:  ", Style::default().fg(GruvboxColors::gray())),
+                            Span::styled(url, Style::default().fg(GruvboxColors::aqua())),
+                        ]));
+                    }
+                } else {
+                    for sf in entry.kind.schema() {
+                        let value = entry.get_field(sf.key);
+                        if value.is_empty() || (sf.key == "nt" && entry.kind != EntryKind::Note) {
+                            continue;
+                        }
+                        let value = if sf.secret {
+                            mask(value)
+                        } else {
+                            value.to_string()
+                        };
+                        let value = match value.lines().count() {
+                            0 | 1 => value,
+                            _ => format!("{} …", value.lines().next().unwrap_or_default()),
+                        };
+                        lines.push(Line::from(vec![
+                            Span::raw("     "),
+                            Span::styled(
+                                format!("├─ {}: ", sf.label),
+                                Style::default().fg(GruvboxColors::gray()),
+                            ),
+                            Span::styled(
+                                value,
+                                Style::default().fg(if sf.secret {
+                                    GruvboxColors::green()
+                                } else {
+                                    GruvboxColors::blue()
+                                }),
+                            ),
+                        ]));
+                    }
+                }
+                for field in &entry.fields {
+                    let value = if field.kind.is_secret() {
+                        mask(&field.value)
+                    } else {
+                        field.value.clone()
+                    };
+                    lines.push(Line::from(vec![
+                        Span::raw("     "),
+                        Span::styled(
+                            format!("├─ {}: ", field.name),
+                            Style::default().fg(GruvboxColors::gray()),
+                        ),
+                        Span::styled(value, Style::default().fg(GruvboxColors::aqua())),
+                    ]));
+                }
+                if let Some(otp) = entry.otp_params() {
+                    lines.push(Line::from(vec![
+                        Span::raw("     "),
+                        Span::styled("├─ TOTP: ", Style::default().fg(GruvboxColors::gray())),
+                        if app.config.present {
+                            Span::styled(MASK, Style::default().fg(GruvboxColors::orange()))
+                        } else {
+                            otp_span(otp)
+                        },
+                    ]));
+                }
+                if !entry.attachments.is_empty() {
+                    let files = entry
+                        .attachments
+                        .iter()
+                        .map(|a| format!("{} ({})", a.name, attachments::fmt_size(a.size)))
+                        .collect::<Vec<_>>()
+                        .join(", ");
                     lines.push(Line::from(vec![
                         Span::raw("     "),
-                        Span::styled("├─ URL:  ", Style::default().fg(GruvboxColors::gray())),
-                        Span::styled(url, Style::default().fg(GruvboxColors::aqua())),
+                        Span::styled("├─ Files: ", Style::default().fg(GruvboxColors::gray())),
+                        Span::styled(files, Style::default().fg(GruvboxColors::aqua())),
                     ]));
                 }
                 if !entry.history.is_empty() {
@@ -131,45 +305,96 @@ pub fn draw_view_pwds(f: &mut Frame, size: Rect, app: &App) {
                 ListItem::new(lines)
             })
             .collect();
-        let list = List::new(items).block(Block::default().borders(Borders::NONE));
-        f.render_widget(list, chunks[1]);
+        (app.scroll_offset, app.page_len) =
+            render_entries(f, chunks[1], items, app.scroll_offset, app.selected_entry);
     }
-    let help = Paragraph::new("↑/↓: Navigate │ E: Edit │ H: History │ F: Clear filter │ Esc: Back")
-        .style(Style::default().fg(GruvboxColors::gray()))
-        .alignment(Alignment::Center);
+    let msg_style = match app.msg_type {
+        MessageType::Success => Style::default().fg(GruvboxColors::green()),
+        MessageType::Error => Style::default().fg(GruvboxColors::red()),
+        MessageType::Info => Style::default().fg(GruvboxColors::blue()),
+        MessageType::None => Style::default().fg(GruvboxColors::fg()),
+    };
+    let help = Paragraph::new(vec![
+        Line::from(Span::styled(app.msg.as_str(), msg_style)),
+        Line::from(Span::styled(
+            "↑/↓/PgUp/PgDn/Home/End: Navigate │ C/U: Copy password/username │ V: Reveal │ P: Presentation │ E: Edit │ H: History │ S: Sort │ *: Favorite │ L: Compact │ F: Clear filter │ Esc: Back",
+            Style::default().fg(GruvboxColors::gray()),
+        )),
+    ])
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
     f.render_widget(help, chunks[2]);
 }
 
-#[allow(clippy::too_many_lines, clippy::cast_sign_loss)]
 pub fn draw_add_pwd(f: &mut Frame, size: Rect, app: &App) {
+    draw_entry_form(
+        f,
+        size,
+        app,
+        "═══ ADD NEW ENTRY ═══",
+        "Fill in the details below",
+        GruvboxColors::green(),
+    );
+}
+
+pub fn draw_edit_pwd(f: &mut Frame, size: Rect, app: &App) {
+    draw_entry_form(
+        f,
+        size,
+        app,
+        "═══ EDIT ENTRY ═══",
+        "Edit entry details (password changes are tracked)",
+        GruvboxColors::orange(),
+    );
+}
+
+/// Shared add/edit form. The rows follow `App::form_slots`, so each entry
+/// type gets its own layout from its schema.
+#[allow(clippy::too_many_lines, clippy::cast_sign_loss)]
+fn draw_entry_form(
+    f: &mut Frame,
+    size: Rect,
+    app: &App,
+    title: &str,
+    subtitle: &str,
+    color: ratatui::style::Color,
+) {
     let area = centered_rect(80, 85, size);
+    let slots = app.form_slots();
+    let mut constraints = vec![Constraint::Length(2), Constraint::Length(1)];
+    let mut rows = Vec::with_capacity(slots.len());
+    for slot in &slots {
+        rows.push(constraints.len());
+        match slot {
+            FormSlot::Field(sf) if sf.multiline => constraints.push(Constraint::Length(4)),
+            FormSlot::Field(sf) if sf.key == "p" => {
+                constraints.push(Constraint::Length(2));
+                constraints.push(Constraint::Length(2));
+                constraints.push(Constraint::Length(2));
+            }
+            FormSlot::Tags | FormSlot::Fields => {
+                constraints.push(Constraint::Length(2));
+                constraints.push(Constraint::Length(3));
+            }
+            _ => constraints.push(Constraint::Length(2)),
+        }
+    }
+    let msg_idx = constraints.len();
+    constraints.push(Constraint::Min(1));
+    constraints.push(Constraint::Length(2));
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(2)
-        .constraints([
-            Constraint::Length(2),
-            Constraint::Length(1),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(3),
-            Constraint::Length(4),
-            Constraint::Min(1),
-            Constraint::Length(2),
-        ])
+        .constraints(constraints)
         .split(area);
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(GruvboxColors::green()))
-        .title("═══ ADD NEW PASSWORD ═══")
+        .border_style(Style::default().fg(color))
+        .title(title.to_string())
         .title_alignment(Alignment::Center)
         .style(Style::default().bg(GruvboxColors::bg0()));
     f.render_widget(block, area);
-    let title = Paragraph::new("Fill in the details below")
+    let title = Paragraph::new(subtitle.to_string())
         .style(Style::default().fg(GruvboxColors::yellow()))
         .alignment(Alignment::Center);
     f.render_widget(title, chunks[0]);
@@ -177,99 +402,135 @@ pub fn draw_add_pwd(f: &mut Frame, size: Rect, app: &App) {
         .fg(GruvboxColors::green())
         .add_modifier(Modifier::BOLD);
     let inactive_style = Style::default().fg(GruvboxColors::gray());
-    let name_field =
-        Paragraph::new(format!("Name: {}", app.n_entry_name)).style(if app.add_fi == 0 {
-            active_style
-        } else {
-            inactive_style
-        });
-    f.render_widget(name_field, chunks[2]);
-    let user_field =
-        Paragraph::new(format!("Username: {}", app.n_entry_user)).style(if app.add_fi == 1 {
-            active_style
-        } else {
-            inactive_style
-        });
-    f.render_widget(user_field, chunks[3]);
-    let pass_field =
-        Paragraph::new(format!("Password: {}", app.n_entry_pass)).style(if app.add_fi == 2 {
+    for (i, (slot, &row)) in slots.iter().zip(&rows).enumerate() {
+        let style = if app.add_fi == i {
             active_style
         } else {
             inactive_style
-        });
-    f.render_widget(pass_field, chunks[4]);
-    if !app.n_entry_pass.is_empty() && app.add_fi == 2 {
-        let strength = crypto::calc_pwd_strength(&app.n_entry_pass);
-        let strength_color = match strength.strength.as_str() {
-            "Weak" => GruvboxColors::red(),
-            "Fair" => GruvboxColors::orange(),
-            "Good" => GruvboxColors::yellow(),
-            "Strong" => GruvboxColors::green(),
-            _ => GruvboxColors::gray(),
         };
-        let bar_width = (35 * strength.percentage) / 100;
-        let empty_width = 35 - bar_width;
-        let bar = format!(
-            "[{}{}] {}% - {}",
-            "█".repeat(bar_width as usize),
-            "─".repeat(empty_width as usize),
-            strength.percentage,
-            strength.strength
-        );
-        let strength_display = Paragraph::new(bar)
-            .style(Style::default().fg(strength_color))
-            .alignment(Alignment::Center);
-        f.render_widget(strength_display, chunks[5]);
-        if !strength.feedback.is_empty() {
-            let feedback_text = format!("↳ {}", strength.feedback.join(", "));
-            let feedback = Paragraph::new(feedback_text)
+        match slot {
+            FormSlot::Kind => {
+                let kind =
+                    Paragraph::new(format!("Type: ◀ {} ▶", app.n_entry_kind.label())).style(style);
+                f.render_widget(kind, chunks[row]);
+            }
+            FormSlot::Name => {
+                let name_field = Paragraph::new(format!("Name: {}", app.n_entry_name)).style(style);
+                f.render_widget(name_field, chunks[row]);
+            }
+            FormSlot::Rotate => {
+                let rotate_field = Paragraph::new(format!(
+                    "Rotate every (days, optional): {}",
+                    app.n_entry_rotate
+                ))
+                .style(style);
+                f.render_widget(rotate_field, chunks[row]);
+            }
+            FormSlot::Folder => {
+                let folder_field = Paragraph::new(format!(
+                    "Folder (optional, e.g. work/aws): {}",
+                    app.n_entry_folder
+                ))
+                .style(style);
+                f.render_widget(folder_field, chunks[row]);
+            }
+            FormSlot::Field(sf) => {
+                let value = app.form_val(sf.key);
+                // A fixed-width mask, so not even the length shows.
+                let shown = if sf.secret && !value.is_empty() && !app.shown(&app.edit_eid) {
+                    MASK.to_string()
+                } else {
+                    value.to_string()
+                };
+                let text = if sf.multiline {
+                    format!("{}:\n{}", sf.label, shown)
+                } else if sf.required {
+                    format!("{}: {}", sf.label, shown)
+                } else {
+                    format!("{} (optional): {}", sf.label, shown)
+                };
+                let field = Paragraph::new(text).style(style).wrap(Wrap { trim: false });
+                f.render_widget(field, chunks[row]);
+                let rules = Paragraph::new(format!(
+                    "F2: Generate │ F3: Site rules ({})",
+                    app.n_entry_policy
+                        .as_ref()
+                        .map_or("none".to_string(), |p| p.summary())
+                ))
                 .style(Style::default().fg(GruvboxColors::gray()))
-                .alignment(Alignment::Center)
-                .wrap(Wrap { trim: true });
-            f.render_widget(feedback, chunks[6]);
+                .alignment(Alignment::Center);
+                if sf.key == "p" && value.is_empty() && app.add_fi == i {
+                    f.render_widget(rules.clone(), chunks[row + 1]);
+                }
+                if sf.key == "p" && !value.is_empty() && app.add_fi == i {
+                    let strength = crypto::calc_pwd_strength(
+                        value,
+                        &[app.n_entry_name.as_str(), app.form_val("u")],
+                    );
+                    let strength_color = match strength.strength.as_str() {
+                        "Weak" => GruvboxColors::red(),
+                        "Fair" => GruvboxColors::orange(),
+                        "Good" => GruvboxColors::yellow(),
+                        "Strong" => GruvboxColors::green(),
+                        _ => GruvboxColors::gray(),
+                    };
+                    let bar_width = (35 * strength.percentage) / 100;
+                    let empty_width = 35 - bar_width;
+                    let bar = format!(
+                        "[{}{}] {}% - {} │ cracked in {}",
+                        "█".repeat(bar_width as usize),
+                        "─".repeat(empty_width as usize),
+                        strength.percentage,
+                        strength.strength,
+                        strength.crack_time
+                    );
+                    let strength_display = Paragraph::new(bar)
+                        .style(Style::default().fg(strength_color))
+                        .alignment(Alignment::Center);
+                    f.render_widget(strength_display, chunks[row + 1]);
+                    // The feedback describes the password, so it follows
+                    // the mask.
+                    if !strength.feedback.is_empty() && app.shown(&app.edit_eid) {
+                        let feedback_text = format!("↳ {}", strength.feedback.join(" · "));
+                        let feedback = Paragraph::new(feedback_text)
+                            .style(Style::default().fg(GruvboxColors::gray()))
+                            .alignment(Alignment::Center)
+                            .wrap(Wrap { trim: true });
+                        f.render_widget(feedback, chunks[row + 2]);
+                    } else {
+                        f.render_widget(rules, chunks[row + 2]);
+                    }
+                }
+            }
+            FormSlot::Tags => {
+                let tags_text = if app.add_fi == i {
+                    format!("Tags: {} ← Enter to add", app.tag_input)
+                } else {
+                    "Tags: (Tab to focus)".to_string()
+                };
+                let tags_input = Paragraph::new(tags_text)
+                    .style(style)
+                    .wrap(Wrap { trim: true });
+                f.render_widget(tags_input, chunks[row]);
+                if !app.n_entry_tags.is_empty() {
+                    let tags_display = app
+                        .n_entry_tags
+                        .iter()
+                        .enumerate()
+                        .map(|(i, tag)| format!("[{}]{} ", i + 1, tag))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let tags_widget = Paragraph::new(format!("Added: {tags_display}"))
+                        .style(Style::default().fg(GruvboxColors::orange()))
+                        .wrap(Wrap { trim: true });
+                    f.render_widget(tags_widget, chunks[row + 1]);
+                }
+            }
+            FormSlot::Fields => {
+                draw_field_editor(f, chunks[row], chunks[row + 1], app, app.add_fi == i);
+            }
         }
     }
-    let url_field =
-        Paragraph::new(format!("URL (optional): {}", app.n_entry_url)).style(if app.add_fi == 3 {
-            active_style
-        } else {
-            inactive_style
-        });
-    f.render_widget(url_field, chunks[7]);
-    let tags_text = if app.add_fi == 4 {
-        format!("Tags: {} ← Enter to add", app.tag_input)
-    } else {
-        "Tags: (Tab to focus)".to_string()
-    };
-    let tags_input = Paragraph::new(tags_text)
-        .style(if app.add_fi == 4 {
-            active_style
-        } else {
-            inactive_style
-        })
-        .wrap(Wrap { trim: true });
-    f.render_widget(tags_input, chunks[8]);
-    if !app.n_entry_tags.is_empty() {
-        let tags_display = app
-            .n_entry_tags
-            .iter()
-            .enumerate()
-            .map(|(i, tag)| format!("[{}]{} ", i + 1, tag))
-            .collect::<Vec<_>>()
-            .join(" ");
-        let tags_widget = Paragraph::new(format!("Added: {tags_display}"))
-            .style(Style::default().fg(GruvboxColors::orange()))
-            .wrap(Wrap { trim: true });
-        f.render_widget(tags_widget, chunks[9]);
-    }
-    let notes = Paragraph::new(format!("Notes:\n{}", app.n_entry_notes))
-        .style(if app.add_fi == 5 {
-            active_style
-        } else {
-            inactive_style
-        })
-        .wrap(Wrap { trim: false });
-    f.render_widget(notes, chunks[10]);
     if !app.msg.is_empty() {
         let msg_style = match app.msg_type {
             MessageType::Success => Style::default().fg(GruvboxColors::green()),
@@ -280,160 +541,81 @@ pub fn draw_add_pwd(f: &mut Frame, size: Rect, app: &App) {
         let msg = Paragraph::new(app.msg.as_str())
             .style(msg_style)
             .alignment(Alignment::Center);
-        f.render_widget(msg, chunks[11]);
+        f.render_widget(msg, chunks[msg_idx]);
     }
-    let help =
-        Paragraph::new("Tab: Next field │ Enter: Add tag/Save │ 1-9: Remove tag │ Esc: Cancel")
-            .style(Style::default().fg(GruvboxColors::gray()))
-            .alignment(Alignment::Center);
-    f.render_widget(help, chunks[12]);
+    let help = Paragraph::new(
+        "Tab: Next field │ ←/→: Type/Field kind │ Enter: Add tag/field/Save │ 1-9: Remove tag │ F2/F3: Generate/Rules │ F4: Reveal │ Esc: Cancel",
+    )
+    .style(Style::default().fg(GruvboxColors::gray()))
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+    f.render_widget(help, chunks[msg_idx + 1]);
 }
 
-#[allow(clippy::too_many_lines, clippy::cast_sign_loss)]
-pub fn draw_edit_pwd(f: &mut Frame, size: Rect, app: &App) {
-    let area = centered_rect(80, 85, size);
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(2)
-        .constraints([
-            Constraint::Length(2),
-            Constraint::Length(1),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(3),
-            Constraint::Length(4),
-            Constraint::Min(1),
-            Constraint::Length(2),
-        ])
-        .split(area);
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(GruvboxColors::orange()))
-        .title("═══ EDIT PASSWORD ═══")
-        .title_alignment(Alignment::Center)
-        .style(Style::default().bg(GruvboxColors::bg0()));
-    f.render_widget(block, area);
-    let title = Paragraph::new("Edit entry details (password changes are tracked)")
-        .style(Style::default().fg(GruvboxColors::yellow()))
-        .alignment(Alignment::Center);
-    f.render_widget(title, chunks[0]);
-    let active_style = Style::default()
-        .fg(GruvboxColors::green())
-        .add_modifier(Modifier::BOLD);
-    let inactive_style = Style::default().fg(GruvboxColors::gray());
-    let name_field =
-        Paragraph::new(format!("Name: {}", app.n_entry_name)).style(if app.add_fi == 0 {
-            active_style
-        } else {
-            inactive_style
-        });
-    f.render_widget(name_field, chunks[2]);
-    let user_field =
-        Paragraph::new(format!("Username: {}", app.n_entry_user)).style(if app.add_fi == 1 {
-            active_style
-        } else {
-            inactive_style
-        });
-    f.render_widget(user_field, chunks[3]);
-    let pass_field =
-        Paragraph::new(format!("Password: {}", app.n_entry_pass)).style(if app.add_fi == 2 {
-            active_style
-        } else {
-            inactive_style
-        });
-    f.render_widget(pass_field, chunks[4]);
-    if !app.n_entry_pass.is_empty() && app.add_fi == 2 {
-        let strength = crypto::calc_pwd_strength(&app.n_entry_pass);
-        let strength_color = match strength.strength.as_str() {
-            "Weak" => GruvboxColors::red(),
-            "Fair" => GruvboxColors::orange(),
-            "Good" => GruvboxColors::yellow(),
-            "Strong" => GruvboxColors::green(),
-            _ => GruvboxColors::gray(),
-        };
-        let bar_width = (35 * strength.percentage) / 100;
-        let empty_width = 35 - bar_width;
-        let bar = format!(
-            "[{}{}] {}% - {}",
-            "█".repeat(bar_width as usize),
-            "─".repeat(empty_width as usize),
-            strength.percentage,
-            strength.strength
-        );
-        let strength_display = Paragraph::new(bar)
-            .style(Style::default().fg(strength_color))
-            .alignment(Alignment::Center);
-        f.render_widget(strength_display, chunks[5]);
-        if !strength.feedback.is_empty() {
-            let feedback_text = format!("↳ {}", strength.feedback.join(", "));
-            let feedback = Paragraph::new(feedback_text)
-                .style(Style::default().fg(GruvboxColors::gray()))
-                .alignment(Alignment::Center)
-                .wrap(Wrap { trim: true });
-            f.render_widget(feedback, chunks[6]);
+/// Renders the custom field input line and the list of fields added so far
+/// for the add/edit forms.
+fn draw_field_editor(f: &mut Frame, input_area: Rect, list_area: Rect, app: &App, focused: bool) {
+    // Only the name of a secret field is echoed while it is typed.
+    let typed = match app.field_input.split_once('=') {
+        Some((name, value))
+            if app.field_kind.is_secret() && !value.is_empty() && !app.shown(&app.edit_eid) =>
+        {
+            format!("{name}={MASK}")
         }
-    }
-    let url_field = Paragraph::new(format!("URL: {}", app.n_entry_url)).style(if app.add_fi == 3 {
-        active_style
-    } else {
-        inactive_style
-    });
-    f.render_widget(url_field, chunks[7]);
-    let tags_text = if app.add_fi == 4 {
-        format!("Tags: {} ← Enter to add", app.tag_input)
+        _ => app.field_input.clone(),
+    };
+    let input_text = if !focused {
+        "Custom fields: (Tab to focus)".to_string()
+    } else if let Some(i) = app.field_sel {
+        format!(
+            "Field {} [{}]: {} ← Enter: Update │ Del: Remove │ PgUp/PgDn: Move",
+            i + 1,
+            app.field_kind.label(),
+            typed
+        )
     } else {
-        "Tags: (Tab to focus)".to_string()
+        format!(
+            "Fields [{}]: {} ← name=value, Enter to add, ↑/↓ to pick",
+            app.field_kind.label(),
+            typed
+        )
     };
-    let tags_input = Paragraph::new(tags_text)
-        .style(if app.add_fi == 4 {
-            active_style
+    let input = Paragraph::new(input_text)
+        .style(if focused {
+            Style::default()
+                .fg(GruvboxColors::green())
+                .add_modifier(Modifier::BOLD)
         } else {
-            inactive_style
+            Style::default().fg(GruvboxColors::gray())
         })
         .wrap(Wrap { trim: true });
-    f.render_widget(tags_input, chunks[8]);
-    if !app.n_entry_tags.is_empty() {
-        let tags_display = app
-            .n_entry_tags
+    f.render_widget(input, input_area);
+    if !app.n_entry_fields.is_empty() {
+        let fields_display = app
+            .n_entry_fields
             .iter()
             .enumerate()
-            .map(|(i, tag)| format!("[{}]{} ", i + 1, tag))
+            .map(|(i, field)| {
+                format!(
+                    "{}[{}]{} ({}): {}",
+                    if app.field_sel == Some(i) { "▶" } else { "" },
+                    i + 1,
+                    field.name,
+                    field.kind.label(),
+                    if field.kind.is_secret() && !app.shown(&app.edit_eid) {
+                        MASK
+                    } else {
+                        &field.value
+                    }
+                )
+            })
             .collect::<Vec<_>>()
-            .join(" ");
-        let tags_widget = Paragraph::new(format!("Tags: {tags_display}"))
-            .style(Style::default().fg(GruvboxColors::orange()))
+            .join("  ");
+        let fields_widget = Paragraph::new(fields_display)
+            .style(Style::default().fg(GruvboxColors::aqua()))
             .wrap(Wrap { trim: true });
-        f.render_widget(tags_widget, chunks[9]);
-    }
-    let notes = Paragraph::new(format!("Notes:\n{}", app.n_entry_notes))
-        .style(if app.add_fi == 5 {
-            active_style
-        } else {
-            inactive_style
-        })
-        .wrap(Wrap { trim: false });
-    f.render_widget(notes, chunks[10]);
-    if !app.msg.is_empty() {
-        let msg_style = match app.msg_type {
-            MessageType::Success => Style::default().fg(GruvboxColors::green()),
-            MessageType::Error => Style::default().fg(GruvboxColors::red()),
-            MessageType::Info => Style::default().fg(GruvboxColors::blue()),
-            MessageType::None => Style::default().fg(GruvboxColors::fg()),
-        };
-        let msg = Paragraph::new(app.msg.as_str())
-            .style(msg_style)
-            .alignment(Alignment::Center);
-        f.render_widget(msg, chunks[11]);
+        f.render_widget(fields_widget, list_area);
     }
-    let help = Paragraph::new("Tab: Next │ Enter: Add tag/Save │ 1-9: Remove tag │ Esc: Cancel")
-        .style(Style::default().fg(GruvboxColors::gray()))
-        .alignment(Alignment::Center);
-    f.render_widget(help, chunks[12]);
 }
 
 pub fn draw_history(f: &mut Frame, size: Rect, app: &App) {
@@ -443,13 +625,14 @@ pub fn draw_history(f: &mut Frame, size: Rect, app: &App) {
         .constraints([
             Constraint::Length(3),
             Constraint::Min(5),
+            Constraint::Length(2),
             Constraint::Length(3),
         ])
         .split(size);
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(GruvboxColors::purple()))
-        .title("═══ PASSWORD HISTORY ═══")
+        .title("═══ HISTORY ═══")
         .title_alignment(Alignment::Center)
         .style(Style::default().bg(GruvboxColors::bg0()));
     f.render_widget(block, size);
@@ -457,13 +640,15 @@ pub fn draw_history(f: &mut Frame, size: Rect, app: &App) {
         if app.selected_entry < app.entry_disp.len() {
             let entry = &app.entry_disp[app.selected_entry];
             if let Some(vault_entry) = vault.e.iter().find(|e| e.id == entry.id) {
-                let title =
-                    Paragraph::new(format!("History for: {} (Last 5 changes)", vault_entry.n))
-                        .style(Style::default().fg(GruvboxColors::yellow()))
-                        .alignment(Alignment::Center);
+                let title = Paragraph::new(format!(
+                    "History for: {} (last {} changes per field)",
+                    vault_entry.n, vault.history_depth
+                ))
+                .style(Style::default().fg(GruvboxColors::yellow()))
+                .alignment(Alignment::Center);
                 f.render_widget(title, chunks[0]);
                 if vault_entry.history.is_empty() {
-                    let empty = Paragraph::new("[ No password changes recorded ]")
+                    let empty = Paragraph::new("[ No changes recorded ]")
                         .style(Style::default().fg(GruvboxColors::gray()))
                         .alignment(Alignment::Center);
                     f.render_widget(empty, chunks[1]);
@@ -475,21 +660,46 @@ pub fn draw_history(f: &mut Frame, size: Rect, app: &App) {
                         .enumerate()
                         .map(|(i, hist)| {
                             let time_ago = App::get_ta(hist.changed_at);
+                            let is_selected = i == app.hist_sel;
+                            let prefix = if is_selected { "▶ " } else { "  " };
+                            let value = if app.config.present
+                                || (hist.field == HistField::Password
+                                    && !app.shown(&vault_entry.id))
+                            {
+                                MASK
+                            } else {
+                                hist.password.as_str()
+                            };
+                            let verb = if hist.restore {
+                                "Replaced by restore"
+                            } else {
+                                "Changed"
+                            };
                             let lines = vec![
                                 Line::from(vec![
                                     Span::styled(
-                                        format!("[{}] ", i + 1),
+                                        prefix,
+                                        Style::default().fg(GruvboxColors::yellow()),
+                                    ),
+                                    Span::styled(
+                                        format!("[{}] {}: ", i + 1, hist.field.label()),
                                         Style::default().fg(GruvboxColors::purple()),
                                     ),
                                     Span::styled(
-                                        &hist.password,
-                                        Style::default().fg(GruvboxColors::green()),
+                                        value,
+                                        if is_selected {
+                                            Style::default()
+                                                .fg(GruvboxColors::green())
+                                                .add_modifier(Modifier::BOLD)
+                                        } else {
+                                            Style::default().fg(GruvboxColors::green())
+                                        },
                                     ),
                                 ]),
                                 Line::from(vec![
-                                    Span::raw("    "),
+                                    Span::raw("      "),
                                     Span::styled(
-                                        format!("Changed: {time_ago}"),
+                                        format!("{verb}: {time_ago}"),
                                         Style::default().fg(GruvboxColors::gray()),
                                     ),
                                 ]),
@@ -499,18 +709,31 @@ pub fn draw_history(f: &mut Frame, size: Rect, app: &App) {
                         })
                         .collect();
                     let list = List::new(items).block(Block::default().borders(Borders::NONE));
-                    f.render_widget(list, chunks[1]);
+                    let mut state = ListState::default().with_selected(Some(app.hist_sel));
+                    f.render_stateful_widget(list, chunks[1], &mut state);
                 }
             }
         }
     }
-    let help = Paragraph::new("Esc: Back")
+    if !app.msg.is_empty() {
+        let msg_style = match app.msg_type {
+            MessageType::Success => Style::default().fg(GruvboxColors::green()),
+            MessageType::Error => Style::default().fg(GruvboxColors::red()),
+            MessageType::Info => Style::default().fg(GruvboxColors::blue()),
+            MessageType::None => Style::default().fg(GruvboxColors::fg()),
+        };
+        let msg = Paragraph::new(app.msg.as_str())
+            .style(msg_style)
+            .alignment(Alignment::Center);
+        f.render_widget(msg, chunks[2]);
+    }
+    let help = Paragraph::new("↑/↓: Navigate │ R: Restore selected │ V: Reveal │ Esc: Back")
         .style(Style::default().fg(GruvboxColors::gray()))
         .alignment(Alignment::Center);
-    f.render_widget(help, chunks[2]);
+    f.render_widget(help, chunks[3]);
 }
 
-pub fn draw_del_pwd(f: &mut Frame, size: Rect, app: &App) {
+pub fn draw_del_pwd(f: &mut Frame, size: Rect, app: &mut App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
@@ -528,7 +751,7 @@ pub fn draw_del_pwd(f: &mut Frame, size: Rect, app: &App) {
         .title_alignment(Alignment::Center)
         .style(Style::default().bg(GruvboxColors::bg0()));
     f.render_widget(block, size);
-    let title = Paragraph::new("⚠ Enter the number of the entry to delete")
+    let title = Paragraph::new("⚠ Enter the number of the entry to move to the trash")
         .style(Style::default().fg(GruvboxColors::orange()))
         .alignment(Alignment::Center);
     f.render_widget(title, chunks[0]);
@@ -548,26 +771,46 @@ pub fn draw_del_pwd(f: &mut Frame, size: Rect, app: &App) {
             .iter()
             .enumerate()
             .map(|(i, entry)| {
+                let is_selected = i == app.selected_entry;
                 ListItem::new(Line::from(vec![
+                    Span::styled(
+                        if is_selected { "▶ " } else { "  " },
+                        Style::default().fg(GruvboxColors::yellow()),
+                    ),
                     Span::styled(
                         format!("[{}] ", i + 1),
                         Style::default().fg(GruvboxColors::red()),
                     ),
-                    Span::styled(&entry.n, Style::default().fg(GruvboxColors::fg())),
+                    Span::styled(
+                        &entry.n,
+                        if is_selected {
+                            Style::default()
+                                .fg(GruvboxColors::fg())
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(GruvboxColors::fg())
+                        },
+                    ),
                 ]))
             })
             .collect();
-        let list = List::new(items).block(Block::default().borders(Borders::NONE));
-        f.render_widget(list, chunks[1]);
+        (app.scroll_offset, app.page_len) =
+            render_entries(f, chunks[1], items, app.scroll_offset, app.selected_entry);
     }
-    let input = Paragraph::new(format!("Entry number: {}", app.input_buffer)).style(
+    let prompt = match app.del_confirm.and_then(|i| app.entry_disp.get(i)) {
+        Some(entry) => format!("Move '{}' to the trash? (y/N)", entry.n),
+        None => format!("Entry number: {}", app.input_buffer),
+    };
+    let input = Paragraph::new(prompt).style(
         Style::default()
             .fg(GruvboxColors::red())
             .add_modifier(Modifier::BOLD),
     );
     f.render_widget(input, chunks[2]);
-    let help = Paragraph::new("Type number │ Enter: Delete │ Esc: Cancel")
-        .style(Style::default().fg(GruvboxColors::gray()))
-        .alignment(Alignment::Center);
+    let help = Paragraph::new(
+        "↑/↓/PgUp/PgDn/Home/End or type number │ Enter, then y: Move to trash │ Esc: Cancel",
+    )
+    .style(Style::default().fg(GruvboxColors::gray()))
+    .alignment(Alignment::Center);
     f.render_widget(help, chunks[3]);
 }
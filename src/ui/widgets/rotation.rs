@@ -0,0 +1,89 @@
+use super::super::app::App;
+use super::super::colors::GruvboxColors;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+pub fn draw_due(f: &mut Frame, size: Rect, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(3),
+        ])
+        .split(size);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(GruvboxColors::orange()))
+        .title("═══ PASSWORD ROTATION ═══")
+        .title_alignment(Alignment::Center)
+        .style(Style::default().bg(GruvboxColors::bg0()));
+    f.render_widget(block, size);
+
+    let due = app
+        .vault
+        .as_ref()
+        .map_or(Vec::new(), |v| v.due(crate::get_timestamp()));
+    let overdue = due.iter().filter(|(_, d)| *d < 0).count();
+    let title = Paragraph::new(format!(
+        "{overdue} expired │ {} expiring soon",
+        due.len() - overdue
+    ))
+    .style(Style::default().fg(GruvboxColors::yellow()))
+    .alignment(Alignment::Center);
+    f.render_widget(title, chunks[0]);
+
+    if due.is_empty() {
+        let empty = Paragraph::new("[ Nothing due for rotation ]")
+            .style(Style::default().fg(GruvboxColors::gray()))
+            .alignment(Alignment::Center);
+        f.render_widget(empty, chunks[1]);
+    } else {
+        let items: Vec<ListItem> = due
+            .iter()
+            .enumerate()
+            .map(|(i, (entry, days))| {
+                let is_selected = i == app.due_sel;
+                let (status, color) = match *days {
+                    d if d < 0 => (format!("expired {} days ago", -d), GruvboxColors::red()),
+                    0 => ("due today".to_string(), GruvboxColors::red()),
+                    d => (format!("due in {d} days"), GruvboxColors::orange()),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        if is_selected { "▶ " } else { "  " },
+                        Style::default().fg(GruvboxColors::yellow()),
+                    ),
+                    Span::styled(
+                        entry.path(),
+                        if is_selected {
+                            Style::default()
+                                .fg(GruvboxColors::yellow())
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(GruvboxColors::fg())
+                        },
+                    ),
+                    Span::styled(format!("  {status}"), Style::default().fg(color)),
+                    Span::styled(
+                        format!("  (changed {})", App::get_ta(entry.pwd_changed())),
+                        Style::default().fg(GruvboxColors::gray()),
+                    ),
+                ]))
+            })
+            .collect();
+        let list = List::new(items).block(Block::default().borders(Borders::NONE));
+        let mut state = ListState::default().with_selected(Some(app.due_sel));
+        f.render_stateful_widget(list, chunks[1], &mut state);
+    }
+    let help = Paragraph::new("↑/↓: Navigate │ Enter: Edit entry │ Esc: Back")
+        .style(Style::default().fg(GruvboxColors::gray()))
+        .alignment(Alignment::Center);
+    f.render_widget(help, chunks[2]);
+}
@@ -0,0 +1,131 @@
+use super::super::app::App;
+use super::super::colors::GruvboxColors;
+use super::super::screens::{MessageType, TrashOp};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+pub fn draw_trash(f: &mut Frame, size: Rect, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(2),
+            Constraint::Length(3),
+        ])
+        .split(size);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(GruvboxColors::red()))
+        .title("═══ TRASH ═══")
+        .title_alignment(Alignment::Center)
+        .style(Style::default().bg(GruvboxColors::bg0()));
+    f.render_widget(block, size);
+
+    let Some(ref vault) = app.vault else {
+        return;
+    };
+    let purge_note = match vault.trash_days {
+        0 => "kept until purged".to_string(),
+        d => format!("purged after {d} days"),
+    };
+    let title = Paragraph::new(format!("{} deleted │ {purge_note}", vault.trash.len()))
+        .style(Style::default().fg(GruvboxColors::yellow()))
+        .alignment(Alignment::Center);
+    f.render_widget(title, chunks[0]);
+
+    if vault.trash.is_empty() {
+        let empty = Paragraph::new("[ Trash is empty ]")
+            .style(Style::default().fg(GruvboxColors::gray()))
+            .alignment(Alignment::Center);
+        f.render_widget(empty, chunks[1]);
+    } else {
+        let items: Vec<ListItem> = vault
+            .trash
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let is_selected = i == app.trash_sel;
+                let deleted = entry.deleted.unwrap_or(entry.last_modified);
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        if is_selected { "▶ " } else { "  " },
+                        Style::default().fg(GruvboxColors::yellow()),
+                    ),
+                    Span::styled(
+                        entry.path(),
+                        if is_selected {
+                            Style::default()
+                                .fg(GruvboxColors::yellow())
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(GruvboxColors::fg())
+                        },
+                    ),
+                    Span::styled(
+                        format!("  deleted {}", App::get_ta(deleted)),
+                        Style::default().fg(GruvboxColors::gray()),
+                    ),
+                ]))
+            })
+            .collect();
+        let list = List::new(items).block(Block::default().borders(Borders::NONE));
+        let mut state = ListState::default().with_selected(Some(app.trash_sel));
+        f.render_stateful_widget(list, chunks[1], &mut state);
+    }
+
+    match app.trash_op {
+        Some(TrashOp::Purge) => {
+            let name = vault.trash.get(app.trash_sel).map_or("", |e| e.n.as_str());
+            let confirm = Paragraph::new(format!("Purge '{name}' for good? (y/N)"))
+                .style(
+                    Style::default()
+                        .fg(GruvboxColors::red())
+                        .add_modifier(Modifier::BOLD),
+                )
+                .alignment(Alignment::Center);
+            f.render_widget(confirm, chunks[2]);
+        }
+        Some(TrashOp::Days) => {
+            let input = Paragraph::new(format!(
+                "Auto-purge after days (0 = never): {}",
+                app.input_buffer
+            ))
+            .style(
+                Style::default()
+                    .fg(GruvboxColors::green())
+                    .add_modifier(Modifier::BOLD),
+            );
+            f.render_widget(input, chunks[2]);
+        }
+        None if !app.msg.is_empty() => {
+            let msg_style = match app.msg_type {
+                MessageType::Success => Style::default().fg(GruvboxColors::green()),
+                MessageType::Error => Style::default().fg(GruvboxColors::red()),
+                MessageType::Info => Style::default().fg(GruvboxColors::blue()),
+                MessageType::None => Style::default().fg(GruvboxColors::fg()),
+            };
+            let msg = Paragraph::new(app.msg.as_str())
+                .style(msg_style)
+                .alignment(Alignment::Center);
+            f.render_widget(msg, chunks[2]);
+        }
+        None => {}
+    }
+
+    let help_text = match app.trash_op {
+        Some(TrashOp::Purge) => "Y: Purge │ Any other key: Cancel",
+        Some(TrashOp::Days) => "Enter: Apply │ Esc: Cancel",
+        None => "↑/↓: Navigate │ Enter/R: Restore │ P: Purge │ A: Auto-purge age │ Esc: Back",
+    };
+    let help = Paragraph::new(help_text)
+        .style(Style::default().fg(GruvboxColors::gray()))
+        .alignment(Alignment::Center);
+    f.render_widget(help, chunks[3]);
+}
@@ -1,10 +1,17 @@
 use super::super::app::App;
 use super::super::colors::GruvboxColors;
+use super::super::screens::{GenRow, MessageType, TagOp, GEN_CHAR_ROWS};
+use super::MASK;
+use crate::models::EntryKind;
+use crate::policy::{AMBIGUOUS, DEFAULT_SYMBOLS};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{
+        Block, Borders, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Wrap,
+    },
     Frame,
 };
 
@@ -27,7 +34,100 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-pub fn draw_search_pwd(f: &mut Frame, size: Rect, app: &App) {
+/// Renders an entry list scrolled from `offset` just far enough to keep
+/// `selected` in view, with a scrollbar once it overflows. Returns the new
+/// offset and how many entries fit on a page from there.
+pub fn render_entries(
+    f: &mut Frame,
+    area: Rect,
+    items: Vec<ListItem>,
+    offset: usize,
+    selected: usize,
+) -> (usize, usize) {
+    let len = items.len();
+    let heights: Vec<usize> = items.iter().map(ListItem::height).collect();
+    let overflows = heights.iter().sum::<usize>() > area.height as usize;
+    let list_area = if overflows {
+        Rect {
+            width: area.width.saturating_sub(1),
+            ..area
+        }
+    } else {
+        area
+    };
+    // Never scroll past the point where the tail of the list fills the
+    // area, e.g. after switching to the compact layout.
+    let mut rows = 0;
+    let tail = heights
+        .iter()
+        .rev()
+        .take_while(|&&h| {
+            rows += h;
+            rows <= area.height as usize
+        })
+        .count();
+    let mut state = ListState::default()
+        .with_offset(offset.min(len.saturating_sub(tail.max(1))))
+        .with_selected(Some(selected.min(len.saturating_sub(1))));
+    f.render_stateful_widget(List::new(items), list_area, &mut state);
+    let offset = state.offset();
+    let mut rows = 0;
+    let page = heights[offset.min(len)..]
+        .iter()
+        .take_while(|&&h| {
+            rows += h;
+            rows <= area.height as usize
+        })
+        .count();
+    if overflows {
+        let mut bar = ScrollbarState::new(len)
+            .position(selected)
+            .viewport_content_length(page);
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .style(Style::default().fg(GruvboxColors::gray())),
+            area,
+            &mut bar,
+        );
+    }
+    (offset, page.max(1))
+}
+
+/// Splits `text` into spans, picking out the chars at the sorted positions
+/// `pos` in an underlined accent color.
+fn highlight<'a>(text: &'a str, pos: &[usize], base: Style) -> Vec<Span<'a>> {
+    let hl = base
+        .fg(GruvboxColors::aqua())
+        .add_modifier(Modifier::UNDERLINED);
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut matched = false;
+    let mut pos = pos.iter().peekable();
+    for (i, (byte, _)) in text.char_indices().enumerate() {
+        let hit = pos.next_if_eq(&&i).is_some();
+        if hit != matched {
+            if byte > start {
+                spans.push(Span::styled(
+                    &text[start..byte],
+                    if matched { hl } else { base },
+                ));
+            }
+            start = byte;
+            matched = hit;
+        }
+    }
+    if start < text.len() {
+        spans.push(Span::styled(
+            &text[start..],
+            if matched { hl } else { base },
+        ));
+    }
+    spans
+}
+
+pub fn draw_search_pwd(f: &mut Frame, size: Rect, app: &mut App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
@@ -45,9 +145,11 @@ pub fn draw_search_pwd(f: &mut Frame, size: Rect, app: &App) {
         .title_alignment(Alignment::Center)
         .style(Style::default().bg(GruvboxColors::bg0()));
     f.render_widget(block, size);
-    let title = Paragraph::new("Search by name, username, URL, or tags")
-        .style(Style::default().fg(GruvboxColors::yellow()))
-        .alignment(Alignment::Center);
+    let title = Paragraph::new(
+        "Fuzzy search by name, username, URL, or tags │ Narrow with name: user: url: tag:",
+    )
+    .style(Style::default().fg(GruvboxColors::yellow()))
+    .alignment(Alignment::Center);
     f.render_widget(title, chunks[0]);
     let search = Paragraph::new(format!("Search: {}", app.search_query)).style(
         Style::default()
@@ -64,26 +166,71 @@ pub fn draw_search_pwd(f: &mut Frame, size: Rect, app: &App) {
         let items: Vec<ListItem> = app
             .entry_disp
             .iter()
-            .map(|entry| {
-                let mut lines = vec![
-                    Line::from(vec![
-                        Span::styled("• ", Style::default().fg(GruvboxColors::orange())),
+            .enumerate()
+            .map(|(i, entry)| {
+                let (name_pos, user_pos) = app
+                    .search_hits
+                    .get(i)
+                    .map_or((&[][..], &[][..]), |h| (&h.name[..], &h.user[..]));
+                let user = highlight(
+                    &entry.u,
+                    user_pos,
+                    Style::default().fg(GruvboxColors::blue()),
+                );
+                let mut head = vec![Span::styled(
+                    if i == app.selected_entry {
+                        "▶ "
+                    } else {
+                        "• "
+                    },
+                    Style::default().fg(GruvboxColors::orange()),
+                )];
+                head.extend(highlight(
+                    &entry.n,
+                    name_pos,
+                    Style::default()
+                        .fg(GruvboxColors::yellow())
+                        .add_modifier(Modifier::BOLD),
+                ));
+                if app.config.compact {
+                    head.push(Span::raw("  "));
+                    head.extend(user);
+                    if !entry.tags.is_empty() {
+                        head.push(Span::styled(
+                            format!("  [{}]", entry.tags.join(", ")),
+                            Style::default().fg(GruvboxColors::orange()),
+                        ));
+                    }
+                    return ListItem::new(Line::from(head));
+                }
+                let mut lines = vec![Line::from(head)];
+                if entry.kind == EntryKind::Login {
+                    let mut line = vec![Span::styled(
+                        "  User: ",
+                        Style::default().fg(GruvboxColors::gray()),
+                    )];
+                    line.extend(user);
+                    lines.push(Line::from(line));
+                    lines.push(Line::from(vec![
+                        Span::styled("  Pass: ", Style::default().fg(GruvboxColors::gray())),
                         Span::styled(
-                            &entry.n,
-                            Style::default()
-                                .fg(GruvboxColors::yellow())
-                                .add_modifier(Modifier::BOLD),
+                            if app.shown(&entry.id) || entry.p.is_empty() {
+                                &entry.p
+                            } else {
+                                MASK
+                            },
+                            Style::default().fg(GruvboxColors::green()),
                         ),
-                    ]),
-                    Line::from(vec![
-                        Span::styled("  User: ", Style::default().fg(GruvboxColors::gray())),
-                        Span::styled(&entry.u, Style::default().fg(GruvboxColors::blue())),
-                    ]),
-                    Line::from(vec![
-                        Span::styled("  Pass: ", Style::default().fg(GruvboxColors::gray())),
-                        Span::styled(&entry.p, Style::default().fg(GruvboxColors::green())),
-                    ]),
-                ];
+                    ]));
+                } else {
+                    lines.push(Line::from(vec![
+                        Span::styled("  Type: ", Style::default().fg(GruvboxColors::gray())),
+                        Span::styled(
+                            entry.kind.label(),
+                            Style::default().fg(GruvboxColors::purple()),
+                        ),
+                    ]));
+                }
                 if !entry.tags.is_empty() {
                     lines.push(Line::from(vec![
                         Span::styled("  Tags: ", Style::default().fg(GruvboxColors::gray())),
@@ -97,26 +244,37 @@ pub fn draw_search_pwd(f: &mut Frame, size: Rect, app: &App) {
                 ListItem::new(lines)
             })
             .collect();
-        let list = List::new(items).block(Block::default().borders(Borders::NONE));
-        f.render_widget(list, chunks[2]);
+        (app.scroll_offset, app.page_len) =
+            render_entries(f, chunks[2], items, app.scroll_offset, app.selected_entry);
     }
-    let help = Paragraph::new("Type to search │ Enter: View results │ Esc: Back")
-        .style(Style::default().fg(GruvboxColors::gray()))
-        .alignment(Alignment::Center);
+    let msg_style = match app.msg_type {
+        MessageType::Success => Style::default().fg(GruvboxColors::green()),
+        MessageType::Error => Style::default().fg(GruvboxColors::red()),
+        MessageType::Info => Style::default().fg(GruvboxColors::blue()),
+        MessageType::None => Style::default().fg(GruvboxColors::fg()),
+    };
+    let help = Paragraph::new(vec![
+        Line::from(Span::styled(app.msg.as_str(), msg_style)),
+        Line::from(Span::styled(
+            "Type to search │ ↑/↓/PgUp/PgDn: Select │ F5: Copy password │ F6: Copy username │ F4: Reveal │ Enter: View results │ Esc: Back",
+            Style::default().fg(GruvboxColors::gray()),
+        )),
+    ])
+    .alignment(Alignment::Center);
     f.render_widget(help, chunks[3]);
 }
 
 pub fn draw_gen_pwd(f: &mut Frame, size: Rect, app: &App) {
-    let area = centered_rect(60, 50, size);
+    let area = centered_rect(60, 70, size);
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(2)
         .constraints([
-            Constraint::Length(3),
-            Constraint::Length(3),
+            Constraint::Length(2),
+            Constraint::Length(GEN_CHAR_ROWS.len() as u16 + 1),
             Constraint::Length(6),
             Constraint::Min(1),
-            Constraint::Length(3),
+            Constraint::Length(2),
         ])
         .split(area);
     let block = Block::default()
@@ -126,45 +284,131 @@ pub fn draw_gen_pwd(f: &mut Frame, size: Rect, app: &App) {
         .title_alignment(Alignment::Center)
         .style(Style::default().bg(GruvboxColors::bg0()));
     f.render_widget(block, area);
-    let title = Paragraph::new("Enter password length (4-64)")
-        .style(Style::default().fg(GruvboxColors::yellow()))
-        .alignment(Alignment::Center);
+    let title = Paragraph::new(if app.gen_ret.is_some() {
+        "Site rules for this entry"
+    } else {
+        "Choose the character classes and rules"
+    })
+    .style(Style::default().fg(GruvboxColors::yellow()))
+    .alignment(Alignment::Center);
     f.render_widget(title, chunks[0]);
-    let length_input = Paragraph::new(format!(
-        "Length: {}",
-        if app.input_buffer.is_empty() {
-            "16"
-        } else {
-            &app.input_buffer
+
+    let p = &app.gen_policy;
+    let class = |min: Option<usize>| match min {
+        None => "off".to_string(),
+        Some(0) => "on".to_string(),
+        Some(n) => format!("at least {n}"),
+    };
+    let flag = |on: bool| if on { "yes" } else { "no" }.to_string();
+    let value = |row: GenRow| match row {
+        GenRow::Mode => p.mode().label().to_string(),
+        GenRow::Length => {
+            if app.input_buffer.is_empty() {
+                p.len.to_string()
+            } else {
+                app.input_buffer.clone()
+            }
         }
-    ))
-    .style(
-        Style::default()
-            .fg(GruvboxColors::green())
-            .add_modifier(Modifier::BOLD),
-    );
-    f.render_widget(length_input, chunks[1]);
+        GenRow::Lower => class(p.lower),
+        GenRow::Upper => class(p.upper),
+        GenRow::Digits => class(p.digits),
+        GenRow::Symbols => class(p.symbols),
+        GenRow::SymbolSet => {
+            if p.symbol_set.is_empty() {
+                format!("{DEFAULT_SYMBOLS} (default)")
+            } else {
+                p.symbol_set.clone()
+            }
+        }
+        GenRow::NoAmbiguous => format!("{} ({AMBIGUOUS})", flag(p.no_ambiguous)),
+        GenRow::NoRepeat => flag(p.no_repeat),
+        GenRow::Words => p.words.unwrap_or_default().to_string(),
+        GenRow::Separator => format!("'{}'", p.separator),
+        GenRow::Capitalize => flag(p.capitalize),
+        GenRow::Insert => flag(p.insert),
+        GenRow::Pattern => p.pattern.clone().unwrap_or_default(),
+    };
+    let rows: Vec<Line> = app
+        .gen_rows()
+        .iter()
+        .enumerate()
+        .map(|(i, &row)| {
+            let selected = i == app.gen_sel;
+            let style = if selected {
+                Style::default()
+                    .fg(GruvboxColors::green())
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(GruvboxColors::fg())
+            };
+            let value = value(row);
+            Line::from(vec![
+                Span::styled(
+                    if selected { "▶ " } else { "  " },
+                    Style::default().fg(GruvboxColors::yellow()),
+                ),
+                Span::styled(format!("{:<18}", row.label()), style),
+                Span::styled(
+                    if selected {
+                        format!("◀ {value} ▶")
+                    } else {
+                        value
+                    },
+                    style,
+                ),
+            ])
+        })
+        .collect();
+    f.render_widget(Paragraph::new(rows), chunks[1]);
+
     if !app.gen_pwd.is_empty() {
         let generated = Paragraph::new(vec![
-            Line::from(""),
             Line::from(Span::styled(
                 "Generated Password:",
                 Style::default().fg(GruvboxColors::gray()),
             )),
             Line::from(""),
             Line::from(Span::styled(
-                &app.gen_pwd,
+                if app.config.present {
+                    MASK
+                } else {
+                    &app.gen_pwd
+                },
                 Style::default()
                     .fg(GruvboxColors::green())
                     .add_modifier(Modifier::BOLD),
             )),
+            Line::from(Span::styled(
+                format!("≈{:.0} bits of entropy", app.gen_policy.entropy_bits()),
+                Style::default().fg(GruvboxColors::gray()),
+            )),
         ])
         .alignment(Alignment::Center);
         f.render_widget(generated, chunks[2]);
     }
-    let help = Paragraph::new("Enter: Generate │ Esc: Back")
+    if !app.msg.is_empty() {
+        let msg_style = match app.msg_type {
+            MessageType::Success => Style::default().fg(GruvboxColors::green()),
+            MessageType::Error => Style::default().fg(GruvboxColors::red()),
+            MessageType::Info => Style::default().fg(GruvboxColors::blue()),
+            MessageType::None => Style::default().fg(GruvboxColors::fg()),
+        };
+        let msg = Paragraph::new(app.msg.as_str())
+            .style(msg_style)
+            .alignment(Alignment::Center);
+        f.render_widget(msg, chunks[3]);
+    }
+    let help = if app.gen_row() == GenRow::Pattern {
+        "c/C consonant · v/V vowel · a/A letter · d digit · s symbol · x letter/digit · [A-F0-9] set · {n} repeat · \\ escape │ Enter: Generate │ Esc: Back"
+    } else if app.gen_ret.is_some() {
+        "↑/↓: Option │ ←/→/Space: Change │ Enter: Generate │ Tab: Use & save rules │ Del: Remove rules │ Esc: Back"
+    } else {
+        "↑/↓: Option │ ←/→/Space: Change │ Type: Length/Symbols/Separator/Pattern │ Enter: Generate │ Esc: Back"
+    };
+    let help = Paragraph::new(help)
         .style(Style::default().fg(GruvboxColors::gray()))
-        .alignment(Alignment::Center);
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
     f.render_widget(help, chunks[4]);
 }
 
@@ -251,3 +495,118 @@ pub fn draw_filter_tags(f: &mut Frame, size: Rect, app: &App) {
         .alignment(Alignment::Center);
     f.render_widget(help, chunks[3]);
 }
+
+pub fn draw_manage_tags(f: &mut Frame, size: Rect, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(size);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(GruvboxColors::purple()))
+        .title("═══ MANAGE TAGS ═══")
+        .title_alignment(Alignment::Center)
+        .style(Style::default().bg(GruvboxColors::bg0()));
+    f.render_widget(block, size);
+    let title = if app.tag_marked.is_empty() {
+        format!("{} tags", app.all_tags.len())
+    } else {
+        format!("Marked for merge: {}", app.tag_marked.join(", "))
+    };
+    let title_widget = Paragraph::new(title)
+        .style(Style::default().fg(GruvboxColors::yellow()))
+        .alignment(Alignment::Center);
+    f.render_widget(title_widget, chunks[0]);
+    if app.all_tags.is_empty() {
+        let empty = Paragraph::new("[ No tags available - Add tags to your passwords first ]")
+            .style(Style::default().fg(GruvboxColors::gray()))
+            .alignment(Alignment::Center);
+        f.render_widget(empty, chunks[1]);
+    } else {
+        let items: Vec<ListItem> = app
+            .all_tags
+            .iter()
+            .enumerate()
+            .map(|(idx, (tag, count))| {
+                let is_selected = idx == app.tag_sel;
+                let mark = if app.tag_marked.contains(tag) {
+                    "[x] "
+                } else {
+                    "[ ] "
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        if is_selected { "▶ " } else { "  " },
+                        Style::default().fg(GruvboxColors::yellow()),
+                    ),
+                    Span::styled(mark, Style::default().fg(GruvboxColors::aqua())),
+                    Span::styled(
+                        match app.vault.as_ref().and_then(|v| v.tag_rotation.get(tag)) {
+                            Some(days) => format!("{tag} ({count} entries, rotate every {days}d)"),
+                            None => format!("{tag} ({count} entries)"),
+                        },
+                        if is_selected {
+                            Style::default()
+                                .fg(GruvboxColors::orange())
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(GruvboxColors::fg())
+                        },
+                    ),
+                ]))
+            })
+            .collect();
+        let list = List::new(items).block(Block::default().borders(Borders::NONE));
+        let mut state = ListState::default().with_selected(Some(app.tag_sel));
+        f.render_stateful_widget(list, chunks[1], &mut state);
+    }
+    let status = match app.tag_op {
+        Some(ref op) => {
+            let affected = app.tag_preview(op);
+            let text = match op {
+                TagOp::Rename(tag) => {
+                    format!(
+                        "Rename '{tag}' to: {}  ({affected} entries)",
+                        app.input_buffer
+                    )
+                }
+                TagOp::Merge(tags) => format!(
+                    "Merge {} into: {}  ({affected} entries)",
+                    tags.join(", "),
+                    app.input_buffer
+                ),
+                TagOp::Delete(tag) => {
+                    format!("Delete '{tag}' from {affected} entries? (y/N)")
+                }
+                TagOp::Interval(tag) => format!(
+                    "Rotate '{tag}' every (days, empty = never): {}  ({affected} entries)",
+                    app.input_buffer
+                ),
+            };
+            Paragraph::new(text).style(
+                Style::default()
+                    .fg(GruvboxColors::green())
+                    .add_modifier(Modifier::BOLD),
+            )
+        }
+        None => Paragraph::new(app.msg.as_str()).style(match app.msg_type {
+            MessageType::Success => Style::default().fg(GruvboxColors::green()),
+            MessageType::Error => Style::default().fg(GruvboxColors::red()),
+            MessageType::Info => Style::default().fg(GruvboxColors::blue()),
+            MessageType::None => Style::default().fg(GruvboxColors::fg()),
+        }),
+    };
+    f.render_widget(status.alignment(Alignment::Center), chunks[2]);
+    let help = Paragraph::new(
+        "↑/↓: Navigate │ Space: Mark │ R: Rename │ M: Merge marked │ D: Delete │ I: Rotation │ Esc: Back",
+    )
+    .style(Style::default().fg(GruvboxColors::gray()))
+    .alignment(Alignment::Center);
+    f.render_widget(help, chunks[3]);
+}
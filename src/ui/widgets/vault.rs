@@ -70,7 +70,7 @@ pub fn draw_create_vault(f: &mut Frame, size: Rect, app: &App) {
     f.render_widget(password_input, chunks[2]);
 
     if !app.input_buffer.is_empty() && app.input_field == InputField::Password {
-        let strength = crypto::calc_pwd_strength(&app.input_buffer);
+        let strength = crypto::calc_pwd_strength(&app.input_buffer, &[]);
         let strength_color = match strength.strength.as_str() {
             "Weak" => GruvboxColors::red(),
             "Fair" => GruvboxColors::orange(),
@@ -81,11 +81,12 @@ pub fn draw_create_vault(f: &mut Frame, size: Rect, app: &App) {
         let bar_width = (35 * strength.percentage) / 100;
         let empty_width = 35 - bar_width;
         let bar = format!(
-            "[{}{}] {}% - {}",
+            "[{}{}] {}% - {} │ cracked in {}",
             "█".repeat(bar_width as usize),
             "─".repeat(empty_width as usize),
             strength.percentage,
-            strength.strength
+            strength.strength,
+            strength.crack_time
         );
         let strength_display = Paragraph::new(bar)
             .style(Style::default().fg(strength_color))
@@ -93,7 +94,7 @@ pub fn draw_create_vault(f: &mut Frame, size: Rect, app: &App) {
         f.render_widget(strength_display, chunks[3]);
 
         if !strength.feedback.is_empty() {
-            let feedback_text = format!("↳ {}", strength.feedback.join(", "));
+            let feedback_text = format!("↳ {}", strength.feedback.join(" · "));
             let feedback = Paragraph::new(feedback_text)
                 .style(Style::default().fg(GruvboxColors::gray()))
                 .alignment(Alignment::Center)
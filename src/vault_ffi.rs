@@ -5,6 +5,9 @@ use std::ptr;
 const VAULT_SUCCESS: c_int = 0;
 const VAULT_ERROR_AUTH: c_int = -4;
 pub const SALT_LENGTH: usize = 16;
+/// libsodium's `crypto_pwhash_ALG_ARGON2I13` and `crypto_pwhash_ALG_ARGON2ID13`.
+pub const ALG_ARGON2I13: i32 = 1;
+pub const ALG_ARGON2ID13: i32 = 2;
 
 #[link(name = "vault_engine", kind = "static")]
 extern "C" {
@@ -33,6 +36,14 @@ extern "C" {
 
     fn vault_gen_salt(salt: *mut c_uchar, salt_len: usize) -> c_int;
 
+    fn vault_random_bytes(buf: *mut c_uchar, len: usize) -> c_int;
+
+    fn vault_random_uniform(upper_bound: u32, out: *mut u32) -> c_int;
+
+    fn vault_kdf_params(opslimit_out: *mut u64, memlimit_out: *mut usize, alg_out: *mut c_int);
+
+    fn vault_hash(in_: *const c_uchar, in_len: usize, out: *mut c_uchar, out_len: usize) -> c_int;
+
     fn vault_free_buffer(buf: *mut c_uchar);
 
     fn vault_secure_zero(ptr: *mut c_uchar, len: usize);
@@ -65,6 +76,72 @@ pub fn generate_salt() -> Result<Vec<u8>, String> {
     }
 }
 
+/// `len` bytes from the libsodium CSPRNG.
+pub fn random_bytes(len: usize) -> Result<Vec<u8>, String> {
+    let mut buf = vec![0u8; len];
+    unsafe {
+        if vault_random_bytes(buf.as_mut_ptr(), len) == VAULT_SUCCESS {
+            Ok(buf)
+        } else {
+            Err("Failed to read random bytes".to_string())
+        }
+    }
+}
+
+/// A uniform value below `upper_bound`, unbiased by libsodium's rejection
+/// sampling.
+pub fn random_uniform(upper_bound: u32) -> Result<u32, String> {
+    let mut out = 0u32;
+    unsafe {
+        if vault_random_uniform(upper_bound, &raw mut out) == VAULT_SUCCESS {
+            Ok(out)
+        } else {
+            Err("Failed to read random bytes".to_string())
+        }
+    }
+}
+
+/// Argon2 parameters the engine derives vault keys with.
+pub struct KdfParams {
+    pub opslimit: u64,
+    pub memlimit: usize,
+    pub alg: i32,
+}
+
+pub fn kdf_params() -> KdfParams {
+    let mut p = KdfParams {
+        opslimit: 0,
+        memlimit: 0,
+        alg: 0,
+    };
+    unsafe {
+        vault_kdf_params(&raw mut p.opslimit, &raw mut p.memlimit, &raw mut p.alg);
+    }
+    p
+}
+
+impl KdfParams {
+    pub fn alg_name(&self) -> &'static str {
+        match self.alg {
+            ALG_ARGON2ID13 => "Argon2id v1.3",
+            ALG_ARGON2I13 => "Argon2i v1.3",
+            _ => "unknown",
+        }
+    }
+}
+
+/// Unkeyed BLAKE2b digest of `data`, `out_len` bytes long (16..=64).
+pub fn hash(data: &[u8], out_len: usize) -> Result<Vec<u8>, String> {
+    let mut out = vec![0u8; out_len];
+    unsafe {
+        if vault_hash(data.as_ptr(), data.len(), out.as_mut_ptr(), out_len) == VAULT_SUCCESS {
+            Ok(out)
+        } else {
+            Err("Failed to hash data".to_string())
+        }
+    }
+}
+
 pub fn encrypt_data(plaintext: &[u8], password: &str, salt: &[u8]) -> Result<Vec<u8>, String> {
     if salt.len() != SALT_LENGTH {
         return Err(format!(